@@ -7,7 +7,8 @@ mod load;
 mod log;
 
 pub use load::load;
-pub use serialize::{Serializer, CssSerializer, JsonSerializer};
+pub use serialize::{Serializer, Breakpoint, CssSerializer, JsonSerializer};
+pub use figtok_tokens::{NameCase, Naming, Replacement};
 use figtok_tokens::{
 	Tokens, 
 	TokenSets, 
@@ -15,70 +16,124 @@ use figtok_tokens::{
 	Token,
 	ReplaceMethod,
 	regex::REGEX_HB,
-	utils::css_stringify,
 	TokenStore,
+	TokenValue,
 };
 use regex::Captures;
+use std::collections::BTreeSet;
 
 pub struct Figtok {
     pub output_path: String,
 	pub tokens: Tokens,
     pub token_sets: TokenSets,
     pub themes: Themes,
+    /// How the names of tokens are written in the output, see `Naming`
+    pub naming: Naming,
+    /// The size of a rem (in pixels), i.e. to work out the slope of fluid values written in rem
+    pub base_font_size: f64,
 }
 
 impl Figtok {
-    pub fn new(tokens: Tokens, token_sets: TokenSets, themes: Themes, output_path: &String) -> Self {
+    pub fn new(tokens: Tokens, token_sets: TokenSets, themes: Themes, naming: Naming, base_font_size: f64, output_path: &str) -> Self {
 		Figtok {
-			output_path: output_path.clone(),
+			output_path: output_path.to_string(),
 			tokens,
             token_sets,
             themes,
+            naming,
+            base_font_size,
 		}
     }
 
 	pub fn serialize(&self, serializer: Box<dyn Serializer>) {
+		self.warn_fluid_fallbacks();
 		serializer.serialize(self)
 	}
+
+	/// Warns about fluid tokens written as their max value rather than `clamp()` in any theme, i.e. because their min
+	/// and max are in different units, see `FluidFallback`
+	fn warn_fluid_fallbacks(&self) {
+		let mut themes: Vec<Option<String>> = self.themes.keys().cloned().map(Some).collect();
+		if themes.is_empty() {
+			themes.push(None);
+		}
+
+		// The same token is usually written in several themes, so the warnings are collected into a set to print each only once.
+		let mut warnings = BTreeSet::new();
+		for theme in &themes {
+			for token in self.tokens(theme) {
+				let Token::Standard(definition) = token else { continue };
+				let TokenValue::Fluid(fluid) = &definition.value else { continue };
+
+				if let Err(fallback) = fluid.preferred(self, theme) {
+					warnings.insert(format!("{} can't be written as clamp() as {}, its max value is written instead.", definition.name, fallback));
+				}
+			}
+		}
+
+		for warning in warnings {
+			warn!("{}", warning);
+		}
+	}
 }
 
 impl TokenStore for Figtok {
-	fn token(&self, id: &String) -> &Token {
+	fn token(&self, id: &str) -> &Token {
 		&self.tokens[id]
 	}
 
 	fn tokens(&self, theme: &Option<String>) -> Vec<&figtok_tokens::Token> {
 		if let Some(key) = theme {
 			let active_sets = self.themes.get(key).unwrap();
-			active_sets.keys().map(|set_name| &self.token_sets[set_name]).flatten().map(|token_id| &self.tokens[token_id]).collect()
+			active_sets.keys().flat_map(|set_name| &self.token_sets[set_name]).map(|token_id| &self.tokens[token_id]).collect()
 		} else {
-			self.tokens.values().map(|t| t).collect::<Vec<&Token>>()
+			self.tokens.values().collect::<Vec<&Token>>()
 		}
 	}
 
 	fn enrich(&self, reference: String, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
-		REGEX_HB
-			.replace_all(&reference, |caps: &Captures| {
-				// Get the reference (dot-notation) from the reference string without the surrounding curly brackets and use it to retrieve the referenced value.
-				let name = &caps[1];
-
-				match replace_method {
-					// Convert the name of the token referenced in the reference string into a CSS var statement so CSS itself can handle the reference.
-					ReplaceMethod::CssVariables => format!("var(--{})", css_stringify(&name.to_string())),
-					// Get the value of the referenced token, so we can replace the handlebar ref in the original reference string.
-					ReplaceMethod::StaticValues => {
-						if let Some(t) = self.tokens(theme).iter().find(|t| t.name() == name) {
-							t.value(self, replace_method, true, theme)
-						} else {
-							// No token with a matching name was found.
-
-							// TODO: Should we panic here instead? Wondering if it\s better to fail and let the user know that there is a token missing...
-							// TODO: Returning "BROKEN_REF" is closer to the behavior with ReplaceMethod:CssVariables as if the ref is broken, the css will still be output, but won't work in practice.
-							String::from("BROKEN_REF")
-						}
+		enrich(self, reference, replace_method, theme)
+	}
+
+	fn naming(&self) -> &Naming {
+		&self.naming
+	}
+
+	fn base_font_size(&self) -> f64 {
+		self.base_font_size
+	}
+}
+
+/// Replaces each reference in the string with the value (or variable) of the token of that name among the tokens of the
+/// given theme in the store, the first token found winning.
+pub(crate) fn enrich(store: &dyn TokenStore, reference: String, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+	REGEX_HB
+		.replace_all(&reference, |caps: &Captures| {
+			// Get the reference (dot-notation) from the reference string without the surrounding curly brackets and use it to retrieve the referenced value.
+			let name = &caps[1];
+
+			match replace_method {
+				// Convert the name of the token referenced in the reference string into a CSS var statement so CSS itself can handle the reference.
+				ReplaceMethod::CssVariables => match store.tokens(theme).iter().find(|t| t.name() == name) {
+					// Private tokens aren't output as variables, so their value is inlined instead.
+					Some(t) if t.is_private() => t.value(store, replace_method, true, theme),
+					Some(t) => format!("var(--{})", t.css_name(store)),
+					// Broken references are still written as a variable, named as the token would have been.
+					None => format!("var(--{})", store.naming().css_name(name, None)),
+				},
+				// Get the value of the referenced token, so we can replace the handlebar ref in the original reference string.
+				ReplaceMethod::StaticValues => {
+					if let Some(t) = store.tokens(theme).iter().find(|t| t.name() == name) {
+						t.value(store, replace_method, true, theme)
+					} else {
+						// No token with a matching name was found.
+
+						// TODO: Should we panic here instead? Wondering if it\s better to fail and let the user know that there is a token missing...
+						// TODO: Returning "BROKEN_REF" is closer to the behavior with ReplaceMethod:CssVariables as if the ref is broken, the css will still be output, but won't work in practice.
+						String::from("BROKEN_REF")
 					}
 				}
-			})
-			.to_string()
-	}
+			}
+		})
+		.to_string()
 }
@@ -1,10 +1,10 @@
 use std::collections::HashMap;
 use std::path::Path;
 use serde_json::Value;
-use figtok_tokens::{TokenSets, Themes, Tokens};
+use figtok_tokens::{Naming, TokenSets, Themes, Tokens};
 
 mod parse;
-use parse::{parse_themes, parse_tokens};
+use parse::{parse_themes, parse_tokens, warn_deprecated_references, warn_name_collisions};
 
 mod utils;
 use utils::read_file;
@@ -17,9 +17,10 @@ enum FileMode {
     MultiFile,
 }
 
-/// Loads all the tokens from the input directory into memory.
-pub fn load(entry_path: &String) -> (Tokens, TokenSets, Themes) {
-    let mode = get_file_mode(&entry_path);
+/// Loads all the tokens from the input directory into memory. Tokens are checked for names that would collide when
+/// written with the given naming.
+pub fn load(entry_path: &str, naming: &Naming) -> (Tokens, TokenSets, Themes) {
+    let mode = get_file_mode(entry_path);
 
     // Load in the raw data using serde, either from a single json file, or by traversing
     // all json files in the directory (entry_path)
@@ -31,12 +32,14 @@ pub fn load(entry_path: &String) -> (Tokens, TokenSets, Themes) {
 	// We also get themes_source, a Vec of serde_json::Value's containing each theme definition from the
 	// $themes file
     let (source_token_sets, source_themes) = match mode {
-        FileMode::SingleFile => load_from_file(&entry_path),
-        FileMode::MultiFile => load_from_dir(&entry_path),
+        FileMode::SingleFile => load_from_file(entry_path),
+        FileMode::MultiFile => load_from_dir(entry_path),
     };
 
     let (tokens, token_sets) = parse_tokens(source_token_sets);
+    warn_deprecated_references(&tokens);
     let themes = parse_themes(source_themes);
+    warn_name_collisions(&tokens, &token_sets, &themes, naming);
 
 	(tokens, token_sets, themes)
 }
@@ -1,6 +1,9 @@
-use std::collections::{HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use serde::de::DeserializeOwned;
-use figtok_tokens::{TokenDefinition, TokenKind, Token, ShadowValue, TokenSets, Tokens, Themes, TokenSet};
+use serde_json::json;
+use figtok_tokens::{TokenDefinition, TokenKind, Token, ShadowValue, BorderValue, TransitionValue, TokenValue, FluidValue, TokenSets, Tokens, Themes, TokenSet, Naming};
+
+use crate::warn;
 
 
 pub fn parse_themes(source: Vec<serde_json::Value>) -> Themes {
@@ -48,7 +51,7 @@ pub fn parse_tokens(source: HashMap<String, HashMap<String, serde_json::Value>>)
 		// as parse_token_set recurses.
 		let mut prefix: Vec<String> = vec![];
 
-		let set_tokens = parse_token_set(&set_name, token_set, Some(&mut prefix));
+		let set_tokens = parse_token_set(&set_name, token_set, Some(&mut prefix), false);
 
 		// Create the token set itself as an empty vec in the token_sets HashMap.
 		token_sets.insert(
@@ -75,37 +78,39 @@ fn parse_token_set(
 	set_name: &String,
 	data: HashMap<String, serde_json::Value>,
 	maybe_prefix: Option<&mut Vec<String>>,
+	private: bool,
 ) -> Vec<Token> {
 	let mut tokens = vec![];
 	let prefix = maybe_prefix.unwrap();
 	
 	for (key, value) in data {
+		// Keys starting with a `$` hold data about the group itself (i.e. `$extensions` or `$description`) rather than tokens.
+		if key.starts_with('$') {
+			continue;
+		}
+
 		let mut id = prefix.clone();
 		id.push(key.clone());
 
-		let kind = value.get("type");
+		// Tokens are private if they, or any group they're in, are marked private with the `figtok.private` extension or
+		// have a name starting with an underscore.
+		let private = private || key.starts_with('_') || is_private(&value);
 
-		match kind {
+		match value.get("type") {
 			// If the "type" property is present, we have a token definition
-			Some(k) => {
-				let token_type: TokenKind = serde_json::from_value(k.clone()).unwrap();
-				let token_name = id.join(".");
-				let token_id = vec![set_name.clone(), token_name.clone()].join(".");
-
-				// do any transformations to the token data based on its kind
-				let token = match token_type {
-					TokenKind::BoxShadow => Token::Shadow(create_token::<ShadowValue>(token_id, token_name, value)),
-					TokenKind::Composition | TokenKind::Typography => Token::Composition(create_token::<serde_json::Value>(token_id, token_name, value)),
-					_ => Token::Standard(create_token::<String>(token_id, token_name, value)),
-				};
-				
-				tokens.push(token);
-			}
+			Some(_) => tokens.push(parse_token(set_name, &id, value, private)),
 			// If the "type" (`kind`) property is not present, we have a nested token set
 			None => {
 				let nested_data: HashMap<String, serde_json::Value> = serde_json::from_value(value).unwrap();
+
+				// A group holding a `min` and `max` dimension (i.e. `font.size.body.min` and `font.size.body.max`) is
+				// also a fluid token scaling between the two, named after the group.
+				if let Some(fluid) = fluid_pair(&id, &nested_data) {
+					tokens.push(parse_token(set_name, &id, fluid, private));
+				}
+
 				// We pass a clone of the id array along as the prefix for all proceeding tokens.
-				for token in parse_token_set(set_name, nested_data, Some(&mut id.clone())) {
+				for token in parse_token_set(set_name, nested_data, Some(&mut id.clone()), private) {
 					tokens.push(token)
 				}
 			}
@@ -115,6 +120,181 @@ fn parse_token_set(
 	tokens
 }
 
+/// Creates a token from its definition, where `id` is the path of the token within its set.
+fn parse_token(set_name: &str, id: &[String], mut value: serde_json::Value, private: bool) -> Token {
+	let kind = value["type"].clone();
+
+	// Privacy inherited from a group (or the token's name) is written to the token's own extensions, so it can
+	// be checked without knowing where the token came from.
+	if private && !mark_private(&mut value) {
+		warn!("{} is private, but its $extensions can't hold the figtok.private flag, so it will be output.", id.join("."));
+	}
+
+	let token_type: TokenKind = serde_json::from_value(kind.clone()).unwrap();
+	let token_name = id.join(".");
+
+	// Types we don't recognize are deserialized as `Other`, their value is still output as it was written.
+	if token_type == TokenKind::Other && kind.as_str() != Some("other") {
+		warn!("Unknown token type {} for {}, treating it as \"other\"", kind, token_name);
+	}
+	let token_id = [set_name, &token_name].join(".");
+
+	// do any transformations to the token data based on its kind
+	match token_type {
+		TokenKind::BoxShadow => Token::Shadow(create_token::<ShadowValue>(token_id, token_name, value)),
+		TokenKind::Border => Token::Border(create_token::<BorderValue>(token_id, token_name, value)),
+		TokenKind::Transition => Token::Transition(create_token::<TransitionValue>(token_id, token_name, value)),
+		TokenKind::Composition => Token::Composition(create_token::<serde_json::Value>(token_id, token_name, value)),
+		TokenKind::Typography => Token::Typography(create_token::<serde_json::Value>(token_id, token_name, value).into()),
+		_ => {
+			// Dimensions can be made fluid with the `figtok.fluid` extension, in which case the value is computed
+			// from the extension rather than taken from the token's own value.
+			let fluid = value.pointer("/$extensions/figtok/fluid").cloned();
+			let mut token: TokenDefinition<TokenValue> = create_token::<serde_json::Value>(token_id, token_name, value).into();
+
+			if let Some(fluid) = fluid {
+				match FluidValue::from_json(&fluid, token.kind) {
+					Ok(fluid) => token.value = TokenValue::Fluid(Box::new(fluid)),
+					// Without both a min and max the value can't scale, so the one that's present is written on its own.
+					Err(present) => {
+						let value = present.unwrap_or_else(|| token.value.clone());
+						warn!("{} is fluid but is missing its min or max, so it's written as {} instead.", token.name, value);
+						token.value = value;
+					}
+				}
+			}
+
+			Token::Standard(token)
+		},
+	}
+}
+
+/// The definition of the fluid token made by a group holding a `min` and `max` token of the same dimension kind, which
+/// references the pair through the `figtok.fluid` extension. Only groups marked with their own `figtok.fluid` extension
+/// make a token, either `true` or the viewport range, i.e. `{ "minViewport": 320, "maxViewport": 1280 }`. If the group
+/// holds only one of the pair, the token is written as that one, see `FluidValue::from_json`
+fn fluid_pair(id: &[String], group: &HashMap<String, serde_json::Value>) -> Option<serde_json::Value> {
+	let mut fluid = match group.get("$extensions").and_then(|e| e.pointer("/figtok/fluid"))? {
+		serde_json::Value::Bool(true) => json!({}),
+		fluid @ serde_json::Value::Object(_) => fluid.clone(),
+		_ => return None,
+	};
+
+	let name = id.join(".");
+	let bound = |key: &str| group.get(key).and_then(|token| token.get("type"));
+	let kind = bound("min").or(bound("max")).filter(|kind| {
+		let dimension = serde_json::from_value::<TokenKind>((*kind).clone()).is_ok_and(|kind| kind.default_unit().is_some());
+		dimension && [bound("min"), bound("max")].into_iter().flatten().all(|other| other == *kind)
+	});
+	let Some(kind) = kind else {
+		warn!("{} is marked fluid, but doesn't hold a min and max dimension of the same type.", name);
+		return None;
+	};
+
+	for key in ["min", "max"].into_iter().filter(|key| bound(key).is_some()) {
+		fluid[key] = json!(format!("{{{}.{}}}", name, key));
+	}
+
+	let value = fluid.get("max").or(fluid.get("min")).cloned();
+	let mut definition = json!({ "type": kind, "value": value, "$extensions": { "figtok": { "fluid": fluid } } });
+	if let Some(description) = group.get("$description") {
+		definition["$description"] = description.clone();
+	}
+
+	Some(definition)
+}
+
+/// Warns about every token that references a deprecated token, so they can be moved to its replacement before it's removed.
+pub fn warn_deprecated_references(tokens: &Tokens) {
+	// The same token may be defined in several sets, so the warnings are collected into a set to print each only once.
+	let mut warnings = BTreeSet::new();
+
+	for token in tokens.values() {
+		for name in token.references() {
+			if let Some(deprecation) = tokens.values().find(|t| t.name() == name).and_then(|t| t.deprecation()) {
+				warnings.insert(format!("{} references deprecated token {}. {}", token.name(), name, deprecation.note()));
+			}
+		}
+	}
+
+	for warning in warnings {
+		warn!("{}", warning);
+	}
+}
+
+/// Warns about tokens with different names that would be written under the same name in the output, i.e. `fontSize` and
+/// `font-size` are both written as `--font-size`, so one would silently replace the other. Tokens are only output
+/// together with the other tokens of their theme, so each theme is checked on its own (or every token, if there are no
+/// themes.)
+pub fn warn_name_collisions(tokens: &Tokens, token_sets: &TokenSets, themes: &Themes, naming: &Naming) {
+	let mut warnings = BTreeSet::new();
+
+	let mut groups: Vec<Vec<&Token>> = themes
+		.values()
+		.map(|theme| {
+			theme
+				.keys()
+				.filter_map(|set_name| token_sets.get(set_name))
+				.flatten()
+				.map(|id| &tokens[id])
+				.collect()
+		})
+		.collect();
+	if groups.is_empty() {
+		groups.push(tokens.values().collect());
+	}
+
+	for group in groups {
+		for (output_name, names) in name_collisions(group, naming) {
+			warnings.insert(format!(
+				"Tokens {} share the output name {}, rename all but one of them.",
+				names.into_iter().collect::<Vec<String>>().join(", "),
+				output_name
+			));
+		}
+	}
+
+	for warning in warnings {
+		warn!("{}", warning);
+	}
+}
+
+/// Groups the names of the given tokens by the name they're written under in the output, keeping only the output names
+/// shared by more than one token. Private tokens aren't output, so can't collide.
+fn name_collisions(tokens: Vec<&Token>, naming: &Naming) -> Vec<(String, BTreeSet<String>)> {
+	let mut output_names: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+	for token in tokens.into_iter().filter(|token| !token.is_private()) {
+		output_names
+			.entry(naming.css_name(&token.name(), Some(&token.set_name())))
+			.or_default()
+			.insert(token.name());
+	}
+
+	output_names.into_iter().filter(|(_, names)| names.len() > 1).collect()
+}
+
+/// Whether a token or group is marked private with the `figtok.private` extension.
+fn is_private(value: &serde_json::Value) -> bool {
+	value.pointer("/$extensions/figtok/private").and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// Sets the `figtok.private` extension of a token, creating the extensions as needed. Returns false if `$extensions` or
+/// `figtok` already holds something other than an object, in which case the token is left as it was.
+fn mark_private(value: &mut serde_json::Value) -> bool {
+	let Some(token) = value.as_object_mut() else { return false };
+	let extensions = token.entry("$extensions").or_insert_with(|| json!({}));
+	let Some(figtok) = extensions.as_object_mut().map(|e| e.entry("figtok").or_insert_with(|| json!({}))) else { return false };
+
+	match figtok.as_object_mut() {
+		Some(figtok) => {
+			figtok.insert(String::from("private"), serde_json::Value::Bool(true));
+			true
+		}
+		None => false,
+	}
+}
+
 fn create_token<T>(id: String, name: String, value: serde_json::Value) -> TokenDefinition<T> 
 where
 	T: DeserializeOwned
@@ -126,3 +306,153 @@ where
 
 	token
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use figtok_tokens::ReplaceMethod;
+
+	#[test]
+	fn marks_private_tokens() {
+		let data = serde_json::from_value(json!({
+			"_palette": { "blue": { "value": "#0000ff", "type": "color" } },
+			"brand": {
+				"$extensions": { "figtok": { "private": true } },
+				"primary": { "value": "{_palette.blue}", "type": "color" }
+			},
+			"accent": { "value": "#ff0000", "type": "color", "$extensions": { "figtok": { "private": true } } },
+			"text": { "value": "{brand.primary}", "type": "color" }
+		}))
+		.unwrap();
+
+		let tokens = parse_token_set(&String::from("global"), data, Some(&mut vec![]), false);
+		let private = |name: &str| tokens.iter().find(|t| t.name() == name).unwrap().is_private();
+
+		assert!(private("_palette.blue"));
+		assert!(private("brand.primary"));
+		assert!(private("accent"));
+		assert!(!private("text"));
+		assert_eq!(tokens.len(), 4);
+	}
+
+	#[test]
+	fn skips_private_flag_when_extensions_are_not_objects() {
+		let data = serde_json::from_value(json!({
+			"_spacing": {
+				"sm": { "value": "4px", "type": "spacing", "$extensions": "legacy" },
+				"md": { "value": "8px", "type": "spacing", "$extensions": { "figtok": true } },
+				"lg": { "value": "16px", "type": "spacing", "$extensions": { "studio.tokens": { "id": "abc" } } }
+			}
+		}))
+		.unwrap();
+
+		let tokens = parse_token_set(&String::from("global"), data, Some(&mut vec![]), false);
+		let private = |name: &str| tokens.iter().find(|t| t.name() == name).unwrap().is_private();
+
+		assert!(!private("_spacing.sm"));
+		assert!(!private("_spacing.md"));
+		assert!(private("_spacing.lg"));
+	}
+
+	#[test]
+	fn pairs_min_and_max_tokens() {
+		let data = serde_json::from_value(json!({
+			"font": {
+				"body": {
+					"$extensions": { "figtok": { "fluid": { "minViewport": 320, "maxViewport": 960 } } },
+					"min": { "value": "16px", "type": "fontSizes" },
+					"max": { "value": "32px", "type": "fontSizes" }
+				},
+				"weight": {
+					"$extensions": { "figtok": { "fluid": true } },
+					"min": { "value": "400", "type": "fontWeights" },
+					"max": { "value": "700", "type": "fontWeights" }
+				},
+				"caption": {
+					"$extensions": { "figtok": { "fluid": true } },
+					"max": { "value": "14px", "type": "fontSizes" }
+				},
+				"label": {
+					"min": { "value": "12px", "type": "fontSizes" },
+					"max": { "value": "14px", "type": "fontSizes" }
+				},
+				"title": {
+					"value": "32px",
+					"type": "fontSizes",
+					"$extensions": { "figtok": { "fluid": { "min": "24px" } } }
+				}
+			}
+		}))
+		.unwrap();
+
+		let tokens = parse_token_set(&String::from("global"), data, Some(&mut vec![]), false);
+		let store = crate::Figtok::new(
+			tokens.into_iter().map(|token| (token.id(), token)).collect(),
+			HashMap::new(),
+			HashMap::new(),
+			Naming::default(),
+			16.0,
+			"",
+		);
+		let value = |name: &str, replace_method| store.tokens.values().find(|t| t.name() == name).map(|t| t.value(&store, replace_method, false, &None));
+
+		assert_eq!(value("font.body", ReplaceMethod::StaticValues), Some(String::from("clamp(16px, 8px + 2.5vw, 32px)")));
+		assert_eq!(
+			value("font.body", ReplaceMethod::CssVariables),
+			Some(String::from("clamp(var(--font-body-min), 8px + 2.5vw, var(--font-body-max))"))
+		);
+		assert_eq!(value("font.body.min", ReplaceMethod::StaticValues), Some(String::from("16px")));
+		// Only dimensions can be fluid.
+		assert_eq!(value("font.weight", ReplaceMethod::StaticValues), None);
+		// Groups only make a fluid token when they're marked fluid.
+		assert_eq!(value("font.label", ReplaceMethod::StaticValues), None);
+		// Without both a min and max, the one that's present is written on its own.
+		assert_eq!(value("font.caption", ReplaceMethod::StaticValues), Some(String::from("14px")));
+		assert_eq!(value("font.title", ReplaceMethod::StaticValues), Some(String::from("24px")));
+	}
+
+	#[test]
+	fn names_tokens_of_nested_sets() {
+		let source = HashMap::from([(
+			String::from("core/brand"),
+			serde_json::from_value(json!({ "color": { "red": { "value": "#ff0000", "type": "color" } } })).unwrap(),
+		)]);
+		let (tokens, _) = parse_tokens(source);
+		let token = &tokens["core.brand.color.red"];
+
+		let naming = Naming { include_set: true, exclude: vec![String::from("core")], ..Naming::default() };
+
+		assert_eq!(token.set_name(), "core.brand");
+		assert_eq!(naming.css_name(&token.name(), Some(&token.set_name())), "brand-color-red");
+		assert_eq!(naming.json_path(&token.name(), Some(&token.set_name())), vec!["brand", "color", "red"]);
+	}
+
+	#[test]
+	fn finds_name_collisions() {
+		let data = serde_json::from_value(json!({
+			"fontSize": { "value": "16px", "type": "fontSizes" },
+			"font-size": { "value": "18px", "type": "fontSizes" },
+			"space": {
+				"1": { "5": { "value": "6px", "type": "spacing" } },
+				"1-5": { "value": "6px", "type": "spacing" },
+				"2": { "value": "8px", "type": "spacing" }
+			},
+			"_font_size": { "value": "16px", "type": "fontSizes" }
+		}))
+		.unwrap();
+
+		let tokens = parse_token_set(&String::from("global"), data, Some(&mut vec![]), false);
+		let collisions = name_collisions(tokens.iter().collect(), &Naming::default())
+			.into_iter()
+			.map(|(output_name, names)| (output_name, names.into_iter().collect::<Vec<String>>()))
+			.collect::<Vec<(String, Vec<String>)>>();
+
+		assert_eq!(
+			collisions,
+			vec![
+				(String::from("font-size"), vec![String::from("font-size"), String::from("fontSize")]),
+				(String::from("space-1-5"), vec![String::from("space.1-5"), String::from("space.1.5")]),
+			]
+		);
+	}
+}
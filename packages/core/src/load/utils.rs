@@ -2,6 +2,7 @@ use std::fs;
 use std::path::Path;
 
 // Define a custom error type for the read_file() function.
+#[allow(dead_code)]
 #[derive(Debug)]
 pub enum ReadFileError {
     InvalidFilepath,
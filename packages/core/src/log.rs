@@ -4,4 +4,12 @@ macro_rules! log {
 		print!("{}{}[Figtok]{}{}: ", termion::color::Fg(termion::color::Green), termion::style::Bold, termion::style::Reset, termion::color::Fg(termion::color::White));
 		println!($($arg)*);
 	}};
-}
\ No newline at end of file
+}
+
+#[macro_export]
+macro_rules! warn {
+	($($arg:tt)*) => {{
+		print!("{}{}[Figtok]{}{}: ", termion::color::Fg(termion::color::Yellow), termion::style::Bold, termion::style::Reset, termion::color::Fg(termion::color::White));
+		println!($($arg)*);
+	}};
+}
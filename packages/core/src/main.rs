@@ -4,8 +4,12 @@ use figtok::{
 	Figtok, 
 	load,
 	Serializer, 
+	Breakpoint,
 	CssSerializer, 
-	JsonSerializer
+	JsonSerializer,
+	NameCase,
+	Naming,
+	Replacement,
 };
 use clap::Parser;
 use std::fs;
@@ -25,14 +29,70 @@ struct Args {
     /// The format to output the tokens to. Currently only supports CSS.
     #[arg(short, long, default_value = "css")]
     format: String,
+
+    /// Also output typography tokens as CSS variables holding the `font` shorthand.
+    #[arg(long)]
+    font_shorthand: bool,
+
+    /// Write pixel values as rem in the CSS output, relative to the base font size.
+    #[arg(long)]
+    rem: bool,
+
+    /// The base font size (in pixels) that rem values are relative to, also used to work out the slope of fluid values
+    /// written in rem.
+    #[arg(long, default_value_t = 16.0)]
+    base_font_size: f64,
+
+    /// Write the tokens of a token set or theme inside a media query, as overrides of the base values. Given as
+    /// `NAME=QUERY`, i.e. `--breakpoint "layout/desktop=(min-width: 1024px)"`. Queries starting with `@` are used as
+    /// they are, i.e. `@container (min-width: 40rem)`. May be given more than once.
+    #[arg(long = "breakpoint", value_name = "NAME=QUERY")]
+    breakpoints: Vec<Breakpoint>,
+
+    /// Include the description of each token, as a comment in CSS output, or alongside its value (with any extensions)
+    /// in JSON output.
+    #[arg(long)]
+    descriptions: bool,
+
+    /// Written before the name of every token, i.e. `--prefix ds` for `--ds-color-primary`.
+    #[arg(long)]
+    prefix: Option<String>,
+
+    /// Start the name of every token with the name of the set it belongs to.
+    #[arg(long)]
+    include_set: bool,
+
+    /// The case token names are written in, one of kebab, camel, snake, pascal or constant. Defaults to kebab in CSS
+    /// output, while JSON keys are written as they are.
+    #[arg(long)]
+    case: Option<NameCase>,
+
+    /// Remove a segment from the names of tokens wherever it appears, i.e. `--exclude-segment global`. May be given more
+    /// than once.
+    #[arg(long = "exclude-segment", value_name = "SEGMENT")]
+    exclude_segments: Vec<String>,
+
+    /// Replace each match of a regular expression in the dot separated names of tokens, i.e. `--replace "^colour\\.=color."`.
+    /// The expression ends at the last `=`, so it may contain `=` while the replacement can't. May be given more than
+    /// once, and replacements are applied in order.
+    #[arg(long = "replace", value_name = "FIND=REPLACE")]
+    replacements: Vec<Replacement>,
 }
 
 fn main() {
     let args = Args::parse();
 
 	let serializer: Box<dyn Serializer> = match args.format.as_str() {
-		"css" => Box::new(CssSerializer::new()),
-		"json" => Box::new(JsonSerializer::new()),
+		"css" => Box::new(CssSerializer {
+			font_shorthand: args.font_shorthand,
+			base_font_size: args.rem.then_some(args.base_font_size),
+			breakpoints: args.breakpoints,
+			descriptions: args.descriptions,
+		}),
+		"json" => Box::new(JsonSerializer {
+			breakpoints: args.breakpoints,
+			descriptions: args.descriptions,
+		}),
 		f => panic!("Unsupported output format {}", f)
 	};
 
@@ -49,9 +109,17 @@ fn main() {
 		panic!("No {} directory found, passed as input directory", &args.entry);
 	};
 
-	let (tokens, token_sets, themes) = load(&args.entry);
+	let naming = Naming {
+		prefix: args.prefix,
+		include_set: args.include_set,
+		case: args.case,
+		exclude: args.exclude_segments,
+		replacements: args.replacements,
+	};
+
+	let (tokens, token_sets, themes) = load(&args.entry, &naming);
 
-	let figtok = Figtok::new(tokens, token_sets, themes, &args.output);
+	let figtok = Figtok::new(tokens, token_sets, themes, naming, args.base_font_size, &args.output);
 
 	figtok.serialize(serializer);
 
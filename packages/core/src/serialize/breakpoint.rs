@@ -0,0 +1,189 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use figtok_tokens::{Naming, ReplaceMethod, Token, TokenSet, TokenStore};
+
+use crate::{enrich, Figtok};
+
+/// Maps a token set or theme to an at-rule (i.e. `@media (min-width: 1024px)` or `@container (min-width: 40rem)`), so
+/// that its tokens are written inside the at-rule as overrides of the base values, rather than as a separate output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakpoint {
+	/// The name of the token set (i.e. `layout/desktop`) or theme.
+	pub name: String,
+	/// The at-rule the tokens are written inside of.
+	pub rule: String,
+}
+
+impl FromStr for Breakpoint {
+	type Err = String;
+
+	/// Parses a breakpoint written as `NAME=QUERY`. A query on its own is a media query, i.e. `layout/desktop=(min-width: 1024px)`,
+	/// while anything starting with an `@` is used as the at-rule as it was written, i.e. `layout/card=@container (min-width: 40rem)`
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (name, query) = s
+			.split_once('=')
+			.map(|(name, query)| (name.trim(), query.trim()))
+			.filter(|(name, query)| !name.is_empty() && !query.is_empty())
+			.ok_or_else(|| format!("expected NAME=QUERY, found \"{}\"", s))?;
+
+		let rule = if query.starts_with('@') {
+			query.to_string()
+		} else {
+			format!("@media {}", query)
+		};
+
+		Ok(Breakpoint { name: name.to_string(), rule })
+	}
+}
+
+/// A group of tokens to be written together, at the top level of the output or inside the at-rule of a breakpoint.
+pub struct SetGroup<'a> {
+	pub rule: Option<&'a str>,
+	/// The ids of the tokens written in the group, in order.
+	pub tokens: TokenSet,
+	/// The store references in the group's tokens are resolved against, see `GroupStore`
+	pub store: GroupStore<'a>,
+}
+
+impl<'a> SetGroup<'a> {
+	/// A group writing the tokens of `sets`, whose references resolve against `sets` ahead of the `base` sets. Inside an
+	/// at-rule, the tokens of the base sets that reference a token of `sets` (directly or through other tokens) are written
+	/// again after them, as their values change with it.
+	fn new(store: &'a Figtok, rule: Option<&'a str>, sets: Vec<&'a String>, base: Vec<&'a String>) -> Self {
+		let mut tokens: Vec<String> = sets.iter().flat_map(|set| store.token_sets[*set].iter().cloned()).collect();
+
+		if rule.is_some() {
+			let mut changed: HashSet<String> = tokens.iter().map(|id| store.token(id).name()).collect();
+			let base_tokens: Vec<&Token> = base
+				.iter()
+				.flat_map(|set| &store.token_sets[*set].0)
+				.map(|id| store.token(id))
+				.filter(|token| !changed.contains(&token.name()))
+				.collect();
+
+			let mut dependents = HashSet::new();
+			loop {
+				let found: Vec<&Token> = base_tokens
+					.iter()
+					.copied()
+					.filter(|token| !dependents.contains(&token.id()) && token.references().iter().any(|name| changed.contains(name)))
+					.collect();
+				if found.is_empty() {
+					break;
+				}
+
+				for token in found {
+					changed.insert(token.name());
+					dependents.insert(token.id());
+				}
+			}
+
+			tokens.extend(base_tokens.iter().map(|token| token.id()).filter(|id| dependents.contains(id)));
+		}
+
+		let mut resolves = sets;
+		resolves.extend(base.into_iter().filter(|set| !resolves.contains(set)).collect::<Vec<&String>>());
+
+		SetGroup { rule, tokens: TokenSet::new(tokens), store: GroupStore { store, sets: resolves } }
+	}
+}
+
+/// A view of the store in which references only resolve against the tokens of the given sets, the first set holding a
+/// token of the referenced name winning. Breakpoint sets override tokens of the same name, so which one a reference
+/// resolves to can't be left to the order of the theme's sets.
+pub struct GroupStore<'a> {
+	store: &'a Figtok,
+	sets: Vec<&'a String>,
+}
+
+impl TokenStore for GroupStore<'_> {
+	fn token(&self, id: &str) -> &Token {
+		self.store.token(id)
+	}
+
+	fn tokens(&self, _theme: &Option<String>) -> Vec<&Token> {
+		self.sets.iter().flat_map(|set| &self.store.token_sets[*set].0).map(|id| self.store.token(id)).collect()
+	}
+
+	fn enrich(&self, reference: String, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+		enrich(self, reference, replace_method, theme)
+	}
+
+	fn naming(&self) -> &Naming {
+		self.store.naming()
+	}
+
+	fn base_font_size(&self) -> f64 {
+		self.store.base_font_size()
+	}
+}
+
+/// The at-rule a token set or theme is mapped to, if any.
+pub fn breakpoint<'a>(breakpoints: &'a [Breakpoint], name: &str) -> Option<&'a str> {
+	breakpoints.iter().find(|b| b.name == name).map(|b| b.rule.as_str())
+}
+
+/// Splits the enabled sets of a theme into the base sets, followed by a group for each of the breakpoints that apply to
+/// it, in the order the breakpoints were given. A breakpoint mapped to one of the theme's sets holds just that set, while
+/// a breakpoint mapped to another theme holds the sets of that theme that the base theme doesn't already include. The
+/// base sets are resolved without any of the breakpoints, which each resolve against their own sets ahead of the base.
+pub fn theme_groups<'a>(store: &'a Figtok, breakpoints: &'a [Breakpoint], theme: &str) -> Vec<SetGroup<'a>> {
+	let enabled = |name: &str| -> Vec<&'a String> {
+		store.themes[name]
+			.iter()
+			.filter(|(_, v)| v.as_str() != "disabled")
+			.map(|(k, _)| k)
+			.collect()
+	};
+	let sets = enabled(theme);
+	let base: Vec<&'a String> = sets.iter().copied().filter(|set| breakpoint(breakpoints, set).is_none()).collect();
+
+	let mut groups = vec![SetGroup::new(store, None, base.clone(), vec![])];
+
+	for b in breakpoints {
+		if sets.contains(&&b.name) {
+			groups.push(SetGroup::new(store, Some(&b.rule), vec![&b.name], base.clone()));
+		} else if store.themes.contains_key(&b.name) {
+			let overrides = enabled(&b.name).into_iter().filter(|set| !sets.contains(set)).collect();
+			groups.push(SetGroup::new(store, Some(&b.rule), overrides, base.clone()));
+		}
+	}
+
+	groups
+}
+
+/// The group a token set is written as when there are no themes. Sets mapped to a breakpoint are written inside its
+/// at-rule, and every set resolves against itself ahead of the sets that aren't mapped to a breakpoint.
+pub fn set_group<'a>(store: &'a Figtok, breakpoints: &'a [Breakpoint], set_name: &'a String) -> SetGroup<'a> {
+	let base = store.token_sets.keys().filter(|set| breakpoint(breakpoints, set).is_none()).collect();
+
+	SetGroup::new(store, breakpoint(breakpoints, set_name), vec![set_name], base)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_media_queries() {
+		assert_eq!(
+			"layout/desktop=(min-width: 1024px)".parse::<Breakpoint>(),
+			Ok(Breakpoint { name: String::from("layout/desktop"), rule: String::from("@media (min-width: 1024px)") })
+		);
+	}
+
+	#[test]
+	fn parses_at_rules() {
+		assert_eq!(
+			"card = @container card (min-width: 40rem)".parse::<Breakpoint>(),
+			Ok(Breakpoint { name: String::from("card"), rule: String::from("@container card (min-width: 40rem)") })
+		);
+	}
+
+	#[test]
+	fn rejects_missing_query() {
+		assert!("layout/desktop".parse::<Breakpoint>().is_err());
+		assert!("layout/desktop=".parse::<Breakpoint>().is_err());
+	}
+}
@@ -1,12 +1,22 @@
 use std::{default::Default, fs, io};
 
 use crate::{log, Figtok, TokenStore};
-use figtok_tokens::{Token, TokenSet};
+use figtok_tokens::{utils::px_to_rem, Token, TokenKind, TokenSet};
 
-use super::Serializer;
+use super::{breakpoint, set_group, theme_groups, Breakpoint, Serializer};
 
 #[derive(Default)]
-pub struct CssSerializer {}
+pub struct CssSerializer {
+    /// Whether typography tokens should also be written as a variable holding the `font` shorthand, alongside their class.
+    pub font_shorthand: bool,
+    /// When set, pixel values are written in rem relative to this base font size (i.e. `16px` is `1rem` with a base of
+    /// 16.) Border widths and shadows are left in pixels, so hairlines stay crisp regardless of the user's font size.
+    pub base_font_size: Option<f64>,
+    /// Token sets and themes whose tokens are written inside a media (or container) query, see `Breakpoint`
+    pub breakpoints: Vec<Breakpoint>,
+    /// Whether the description of each token should be written as a comment before it.
+    pub descriptions: bool,
+}
 impl Serializer for CssSerializer {
     fn serialize(&self, store: &Figtok) {
         if !store.themes.is_empty() {
@@ -22,12 +32,12 @@ impl Serializer for CssSerializer {
 }
 impl CssSerializer {
     pub fn new() -> Self {
-        CssSerializer {}
+        CssSerializer::default()
     }
 
     fn serialize_token_set(
         &self,
-        store: &Figtok,
+        store: &dyn TokenStore,
         token_set: &TokenSet,
         theme_name: &Option<String>,
     ) -> (String, String) {
@@ -36,49 +46,95 @@ impl CssSerializer {
 
         for id in token_set {
             let token = store.token(id);
-            let token_value =
-                &token.serialize(store, figtok_tokens::ReplaceMethod::StaticValues, theme_name);
+
+            // Private tokens are only inlined into the tokens that reference them.
+            if token.is_private() {
+                continue;
+            }
+            let serialized = match token.deprecation().and_then(|deprecation| deprecation.replacement.as_ref()) {
+                // Deprecated variables that have been replaced are written as an alias of their replacement, so the old
+                // name keeps working.
+                Some(replacement) if !matches!(token, Token::Composition(_) | Token::Typography(_)) => {
+                    format!(
+                        "--{}: {};",
+                        token.css_name(store),
+                        store.enrich(format!("{{{}}}", replacement), figtok_tokens::ReplaceMethod::CssVariables, theme_name)
+                    )
+                }
+                _ => token.serialize_with(store, figtok_tokens::ReplaceMethod::StaticValues, theme_name, |property, value| self.rem(property, value)),
+            };
+            let token_value = &format!("{}{}", self.comment(token), serialized);
 
             match token {
-                Token::Standard(_) | Token::Shadow(_) => {
+                Token::Standard(_) | Token::Shadow(_) | Token::Border(_) | Token::Transition(_) => {
                     variables.push_str(token_value);
                 }
                 Token::Composition(_) => {
                     styles.push_str(token_value);
                 }
+                Token::Typography(_) => {
+                    styles.push_str(token_value);
+
+                    if self.font_shorthand {
+                        if let Some(font) = token.serialize_font_shorthand_with(store, figtok_tokens::ReplaceMethod::StaticValues, theme_name, |property, value| self.rem(property, value)) {
+                            variables.push_str(&font);
+                        }
+                    }
+                }
             }
         }
 
         (variables, styles)
     }
 
+    /// Writes the description of the token as a comment (if descriptions are enabled and the token has one), followed by
+    /// a note on its deprecation if it's deprecated.
+    fn comment(&self, token: &Token) -> String {
+        let description = token.description().filter(|_| self.descriptions).map(String::from);
+        let deprecation = token.deprecation().map(|deprecation| deprecation.note());
+
+        [description, deprecation]
+            .into_iter()
+            .flatten()
+            // A comment can't be closed early by its contents.
+            .map(|comment| format!("/* {} */", comment.replace("*/", "* /")))
+            .collect()
+    }
+
+    /// Converts the pixel values in the value of a css property to rem, if a base font size was given. Border widths
+    /// (including those in a `border` shorthand) and shadows are left alone, as are text and assets which aren't lengths.
+    fn rem(&self, property: &str, value: String) -> String {
+        let border_width = property.starts_with("border") && !property.ends_with("radius");
+        let excluded = [TokenKind::BoxShadow, TokenKind::Text, TokenKind::Asset].iter().any(|kind| kind.to_string() == property);
+        let converts = !border_width && !excluded;
+
+        match self.base_font_size {
+            Some(base_font_size) if converts => px_to_rem(&value, base_font_size),
+            _ => value,
+        }
+    }
+
     pub fn serialize_themes(&self, store: &Figtok) {
         log!("Detected {} themes...", store.themes.len());
 
-        for (name, sets) in &store.themes {
+        // Themes mapped to a breakpoint are written as overrides in the stylesheet of every other theme, rather than
+        // on their own.
+        for name in store.themes.keys().filter(|name| breakpoint(&self.breakpoints, name).is_none()) {
             log!("Generating Theme: {}", name);
 
-            let mut variables = String::new();
-            let mut classes = String::new();
+            let mut output = String::new();
 
-            for set_name in sets
-                .into_iter()
-                .filter(|(_, v)| v.as_str() != "disabled")
-                .map(|(k, _)| k)
-                .collect::<Vec<&String>>()
-            {
-                let token_set: &TokenSet = &store.token_sets[set_name];
+            for group in theme_groups(store, &self.breakpoints, name) {
+                let (variables, classes) = self.serialize_token_set(&group.store, &group.tokens, &Some(name.clone()));
 
-                let output = self.serialize_token_set(store, token_set, &Some(name.clone()));
-                variables.push_str(&output.0);
-                classes.push_str(&output.1);
+                output.push_str(&self.stylesheet(group.rule, variables, classes));
             }
 
             // Write the css file.
             let name_parts: Vec<&str> = name.split("/").map(|s| s.trim()).collect();
             let file_name = [store.output_path.to_string(), name_parts.join("-")].join("/");
 
-            let _ = self.write_file(file_name, format!(":root{{{}}}\n{}", variables, classes));
+            let _ = self.write_file(file_name, output);
         }
     }
 
@@ -86,10 +142,11 @@ impl CssSerializer {
         log!("Detected {} token sets...", store.token_sets.len());
 
         // create a .css file for every token set
-        for (set_name, token_set) in &store.token_sets {
+        for set_name in store.token_sets.keys() {
             log!("Generating Token Set: {}", set_name);
 
-            let (variables, styles) = self.serialize_token_set(store, token_set, &None);
+            let group = set_group(store, &self.breakpoints, set_name);
+            let (variables, styles) = self.serialize_token_set(&group.store, &group.tokens, &None);
 
             // Split the set name by any /'s in case they are nested but remove the
             // last portion as this will be the file name not a directory
@@ -100,11 +157,116 @@ impl CssSerializer {
             };
 
             // Ensure the directories we need exist for the token set
-            fs::create_dir_all(vec![store.output_path.clone(), dir.to_string()].join("/")).unwrap();
+            fs::create_dir_all([store.output_path.clone(), dir.to_string()].join("/")).unwrap();
 
-            // Write the css file.
+            // Write the css file. Without themes there's no shared stylesheet to add the overrides of a set mapped to a
+            // breakpoint to, so its file holds the at-rule instead.
             let file_name = [store.output_path.to_string(), set_name.to_string()].join("/");
-            let _ = self.write_file(file_name, format!(":root{{{}}}\n{}", variables, styles));
+            let _ = self.write_file(file_name, self.stylesheet(group.rule, variables, styles));
+        }
+    }
+
+    /// Writes the variables and classes of a group of token sets, inside the at-rule of a breakpoint if one is given.
+    fn stylesheet(&self, rule: Option<&str>, variables: String, classes: String) -> String {
+        match rule {
+            Some(rule) => format!("{}{{:root{{{}}}{}}}\n", rule, variables, classes),
+            None => format!(":root{{{}}}\n{}", variables, classes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use figtok_tokens::{Naming, TokenDefinition};
+    use serde_json::json;
+
+    fn token(set: &str, name: &str, kind: TokenKind, value: serde_json::Value) -> Token {
+        let definition = TokenDefinition {
+            id: format!("{}.{}", set.replace('/', "."), name),
+            name: name.to_string(),
+            value,
+            kind,
+            description: None,
+            extensions: None,
+            deprecated: None,
+        };
+
+        match kind {
+            TokenKind::Composition => Token::Composition(definition),
+            _ => Token::Standard(definition.into()),
         }
     }
+
+    #[test]
+    fn converts_values_to_rem() {
+        let tokens = vec![
+            token("global", "size.16px", TokenKind::Spacing, json!("32px")),
+            token("global", "border.thin", TokenKind::BorderWidth, json!("2px")),
+            token("global", "label", TokenKind::Text, json!("16px")),
+            token("global", "card", TokenKind::Composition, json!({ "borderWidth": "2px", "borderRadius": "8px", "spacing": "16px" })),
+        ];
+        let token_set = TokenSet::new(tokens.iter().map(Token::id).collect());
+        let store = Figtok::new(
+            tokens.into_iter().map(|token| (token.id(), token)).collect(),
+            HashMap::new(),
+            HashMap::new(),
+            Naming::default(),
+            16.0,
+            "",
+        );
+        let serializer = CssSerializer { base_font_size: Some(16.0), ..CssSerializer::default() };
+
+        let (variables, classes) = serializer.serialize_token_set(&store, &token_set, &None);
+
+        assert_eq!(variables, "--size-16px: 2rem;--border-thin: 2px;--label: \"16px\";");
+        assert_eq!(classes, ".card {border-width: 2px;border-radius: 0.5rem;spacing: 1rem;}");
+    }
+
+    #[test]
+    fn resolves_breakpoints_apart_from_the_base() {
+        let sets = [
+            ("global", vec![
+                token("global", "gutter", TokenKind::Spacing, json!("0.5rem")),
+                token("global", "section.gap", TokenKind::Spacing, json!("{gutter} * 2")),
+                token("global", "section.inset", TokenKind::Spacing, json!("{section.gap}")),
+                token("global", "radius", TokenKind::BorderRadius, json!("4px")),
+            ]),
+            ("layout/desktop", vec![token("layout/desktop", "gutter", TokenKind::Spacing, json!("1.5rem"))]),
+        ];
+        let token_sets = sets.iter().map(|(set, tokens)| (set.to_string(), TokenSet::new(tokens.iter().map(Token::id).collect()))).collect();
+        let themes = HashMap::from([(
+            String::from("web"),
+            HashMap::from([(String::from("global"), String::from("enabled")), (String::from("layout/desktop"), String::from("enabled"))]),
+        )]);
+        let store = Figtok::new(
+            sets.into_iter().flat_map(|(_, tokens)| tokens).map(|token| (token.id(), token)).collect(),
+            token_sets,
+            themes,
+            Naming::default(),
+            16.0,
+            "",
+        );
+        let serializer = CssSerializer { breakpoints: vec!["layout/desktop=(min-width: 1024px)".parse().unwrap()], ..CssSerializer::default() };
+
+        let output = theme_groups(&store, &serializer.breakpoints, "web")
+            .into_iter()
+            .map(|group| {
+                let (variables, classes) = serializer.serialize_token_set(&group.store, &group.tokens, &None);
+                serializer.stylesheet(group.rule, variables, classes)
+            })
+            .collect::<Vec<String>>();
+
+        // The base values never see the breakpoint's gutter, while the tokens depending on it are written again inside
+        // the media query.
+        assert_eq!(
+            output,
+            vec![
+                ":root{--gutter: 0.5rem;--section-gap: 1rem;--section-inset: 1rem;--radius: 4px;}\n",
+                "@media (min-width: 1024px){:root{--gutter: 1.5rem;--section-gap: 3rem;--section-inset: 3rem;}}\n",
+            ]
+        );
+    }
 }
@@ -3,14 +3,24 @@ use merge_struct::merge;
 use serde_json::json;
 
 use crate::{Figtok, TokenStore, log};
-use figtok_tokens::{ReplaceMethod, TokenSet};
+use figtok_tokens::{ReplaceMethod, Token};
 
 use super::{
+	breakpoint,
+	set_group,
+	theme_groups,
+	Breakpoint,
 	Serializer,
 };
 
 #[derive(Default)]
-pub struct JsonSerializer {}
+pub struct JsonSerializer {
+	/// Token sets and themes whose values are grouped under the at-rule of a breakpoint, see `Breakpoint`
+	pub breakpoints: Vec<Breakpoint>,
+	/// Whether each token should be written as an object holding its value alongside its description and extensions,
+	/// i.e. `{ "value": "8px", "description": "Gap between cards" }`, rather than as its value alone.
+	pub descriptions: bool,
+}
 impl Serializer for JsonSerializer {
 	fn serialize(&self, store: &Figtok) {
 		if !store.themes.is_empty() {
@@ -28,24 +38,32 @@ impl Serializer for JsonSerializer {
 }
 impl JsonSerializer {
 	pub fn new() -> Self {
-		JsonSerializer {}
+		JsonSerializer::default()
 	}
 
 	pub fn serialize_themes(&self, store: &Figtok) {
 		log!("Detected {} themes...", store.themes.len());
 
-		for (name, sets) in &store.themes {
+		// Themes mapped to a breakpoint are written as overrides in the output of every other theme, rather than on their own.
+		for name in store.themes.keys().filter(|name| breakpoint(&self.breakpoints, name).is_none()) {
 			let mut value = json!({});
 			log!("Generating Theme: {}", name);
 
-			for set_name in sets.into_iter().filter(|(_, v)| v.as_str() != "disabled").map(|(k, _)| k).collect::<Vec<&String>>() {
-				let token_set: &TokenSet  = &store.token_sets[set_name];
+			for group in theme_groups(store, &self.breakpoints, name) {
+				let mut group_value = json!({});
 
-				for id in token_set {
+				for id in &group.tokens {
 					let token = store.token(id);
 
-					value = merge(&value, &token.to_json(store, ReplaceMethod::StaticValues, &Some(name.clone()))).unwrap();
-				};
+					group_value = merge(&group_value, &self.token_json(token, &group.store, &Some(name.clone()))).unwrap();
+				}
+
+				// The values of each breakpoint are grouped under its at-rule, i.e. `{ "@media (min-width: 1024px)": { ... } }`
+				if let Some(rule) = group.rule {
+					group_value = json!({ rule: group_value });
+				}
+
+				value = merge(&value, &group_value).unwrap();
 			}
 
 			// Write the css file.
@@ -60,15 +78,20 @@ impl JsonSerializer {
 	pub fn serialize_token_sets(&self, store: &Figtok) {
 		log!("Detected {} token sets...", store.token_sets.len());
 
-		for (set_name, token_set) in &store.token_sets {
+		for set_name in store.token_sets.keys() {
 			let mut value = json!({});
+			let group = set_group(store, &self.breakpoints, set_name);
 
-			for id in token_set {
+			for id in &group.tokens {
 				let token = store.token(id);
 
-				value = merge(&value, &token.to_json(store, ReplaceMethod::StaticValues, &None)).unwrap();
+				value = merge(&value, &self.token_json(token, &group.store, &None)).unwrap();
 			};
 
+			if let Some(rule) = group.rule {
+				value = json!({ rule: value });
+			}
+
 			// Now we make sure the output directory exists, and write the CSS file to disk
 
 			// Split the set name by any /'s in case they are nested but remove the
@@ -89,4 +112,37 @@ impl JsonSerializer {
 			);
 		}
 	}
-}
\ No newline at end of file
+
+	/// Writes the token as json, including its description and extensions when enabled. Private tokens are only inlined
+	/// into the tokens that reference them, so are written as an empty object that doesn't change the merged output.
+	fn token_json(&self, token: &Token, store: &dyn TokenStore, theme: &Option<String>) -> serde_json::Value {
+		if token.is_private() {
+			return json!({});
+		}
+
+		let mut value = token.to_json(store, ReplaceMethod::StaticValues, theme);
+
+		if self.descriptions {
+			// The value of the token is nested under each part of its name, i.e. `{ "spacing": { "gap": "8px" } }`
+			let pointer = token
+				.json_path(store)
+				.iter()
+				.map(|key| format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+				.collect::<String>();
+
+			if let Some(leaf) = value.pointer_mut(&pointer) {
+				let mut j = json!({ "value": leaf.take() });
+				if let Some(description) = token.description() {
+					j["description"] = json!(description);
+				}
+				if let Some(extensions) = token.extensions() {
+					j["$extensions"] = extensions.clone();
+				}
+
+				*leaf = j;
+			}
+		}
+
+		value
+	}
+}
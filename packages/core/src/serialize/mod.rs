@@ -1,3 +1,6 @@
+mod breakpoint;
+pub use breakpoint::*;
+
 mod css;
 use std::io;
 
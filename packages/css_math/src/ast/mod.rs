@@ -0,0 +1,280 @@
+use std::fmt;
+
+use crate::token::Span;
+
+/// A node in the abstract syntax tree of a CSS Math statement, along with the location in the input it was parsed from.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Expression {
+	pub kind: ExpressionKind,
+	pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ExpressionKind {
+	/// A plain number with no unit, i.e. `1.5`
+	Number(f64),
+	/// A number with a unit, i.e. `16px` or `100%`
+	Dimension(f64, String),
+	/// A reference to a css variable, i.e. `var(--spacing-base)`
+	Variable(String),
+	/// An operation on two sub-expressions, i.e. `16px * 2`
+	Binary(Operator, Box<Expression>, Box<Expression>),
+	/// A call to a CSS math function, i.e. `clamp(1rem, 2vw, 3rem)`
+	Function(Function, Vec<Expression>),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Function {
+	Calc,
+	Min,
+	Max,
+	Clamp,
+	// Tokens Studio math functions, these aren't part of CSS so are printed as their closest CSS equivalent.
+	Round,
+	RoundTo,
+	Floor,
+	Ceil,
+	Sqrt,
+	Pow,
+	Abs,
+	/// CSS round(), rounding a value to a multiple of a step, i.e. `round(down, 10.5px, 1px)`
+	RoundStep(RoundingStrategy),
+}
+
+/// How CSS round() picks a multiple of the step when a value falls between two of them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RoundingStrategy {
+	Nearest,
+	Up,
+	Down,
+	ToZero,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operator {
+	Add,
+	Subtract,
+	Multiply,
+	Divide,
+	/// Exponentiation, i.e. `1.25 ^ 3`, as supported by Tokens Studio.
+	Power,
+}
+
+impl Expression {
+	pub fn new(kind: ExpressionKind, span: Span) -> Self {
+		Expression { kind, span }
+	}
+}
+
+impl Expression {
+	/// Whether the expression is a call to a math function, and can therefore be used as a CSS value without being
+	/// wrapped in `calc()`
+	pub fn is_function(&self) -> bool {
+		matches!(self.kind, ExpressionKind::Function(_, _))
+	}
+}
+
+impl Expression {
+	/// The unit the expression resolves to (`Some(None)` for a plain number), if it can be known without the value of
+	/// any `var()`, i.e. `var(--a) * 2px` is always in px, but `var(--a) + 2px` is only known once it's computed.
+	pub(crate) fn unit(&self) -> Option<Option<&str>> {
+		match &self.kind {
+			ExpressionKind::Number(_) => Some(None),
+			ExpressionKind::Dimension(_, unit) => Some(Some(unit)),
+			ExpressionKind::Variable(_) => None,
+			// Exponents only apply to plain numbers.
+			ExpressionKind::Binary(Operator::Power, _, _) | ExpressionKind::Function(Function::Sqrt | Function::Pow, _) => {
+				Some(None)
+			}
+			// One side of a multiplication is always a number, so the other side decides the unit.
+			ExpressionKind::Binary(Operator::Multiply, lhs, rhs) => match (lhs.unit(), rhs.unit()) {
+				(Some(Some(unit)), _) | (_, Some(Some(unit))) => Some(Some(unit)),
+				(Some(None), Some(None)) => Some(None),
+				_ => None,
+			},
+			// The right hand side of a division is always a number.
+			ExpressionKind::Binary(Operator::Divide, lhs, _) => lhs.unit(),
+			ExpressionKind::Binary(_, lhs, rhs) => shared_unit([lhs.as_ref(), rhs.as_ref()].into_iter()),
+			ExpressionKind::Function(
+				Function::Round | Function::RoundTo | Function::Floor | Function::Ceil | Function::Abs | Function::RoundStep(_),
+				arguments,
+			) => arguments[0].unit(),
+			ExpressionKind::Function(_, arguments) => shared_unit(arguments.iter()),
+		}
+	}
+}
+
+/// The unit shared by every operand, if there is one.
+fn shared_unit<'a>(mut operands: impl Iterator<Item = &'a Expression>) -> Option<Option<&'a str>> {
+	let unit = operands.next()?.unit()?;
+
+	operands.all(|operand| operand.unit() == Some(unit)).then_some(unit)
+}
+
+impl fmt::Display for Expression {
+	/// Prints the expression with the whitespace CSS requires around operators (i.e. `10px - 2px`, never `10px-2px`),
+	/// only adding the parentheses needed to preserve the structure of the tree.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.kind {
+			// Numbers are printed exactly as they were written, the simplifier rounds the values it folds.
+			ExpressionKind::Number(n) => write!(f, "{}", n),
+			ExpressionKind::Dimension(n, unit) => write!(f, "{}{}", n, unit),
+			ExpressionKind::Variable(var) => write!(f, "{}", var),
+			// CSS has no exponent operator, but pow() does the same thing.
+			ExpressionKind::Binary(Operator::Power, lhs, rhs) => write!(f, "pow({}, {})", lhs, rhs),
+			ExpressionKind::Binary(op, lhs, rhs) => {
+				write_operand(f, lhs, op, false)?;
+				write!(f, " {} ", op)?;
+				write_operand(f, rhs, op, true)
+			}
+			ExpressionKind::Function(Function::RoundStep(strategy), arguments) => {
+				write!(f, "round({}", strategy)?;
+				for argument in arguments {
+					write!(f, ", {}", argument)?;
+				}
+				write!(f, ")")
+			}
+			// round(), floor(), ceil() and roundTo() are printed as CSS round(), which needs a step with the same unit as
+			// the value. When the unit can't be known they're printed as written, and `to_css` refuses to format them.
+			ExpressionKind::Function(
+				function @ (Function::Round | Function::Floor | Function::Ceil | Function::RoundTo),
+				arguments,
+			) if arguments[0].unit().is_some() => {
+				let unit = arguments[0].unit().flatten().unwrap_or_default();
+				let strategy = match function {
+					Function::Floor => RoundingStrategy::Down,
+					Function::Ceil => RoundingStrategy::Up,
+					_ => RoundingStrategy::Nearest,
+				};
+				write!(f, "round({}, {}, ", strategy, arguments[0])?;
+
+				// roundTo(x, n) rounds to n decimal places, which is a step of 0.1^n.
+				match arguments.get(1).map(|places| (places, &places.kind)) {
+					Some((_, ExpressionKind::Number(places))) => write!(f, "{}{})", 10f64.powf(-places), unit),
+					Some((places, _)) if unit.is_empty() => write!(f, "pow(0.1, {}))", places),
+					Some((places, _)) => write!(f, "pow(0.1, {}) * 1{})", places, unit),
+					None => write!(f, "1{})", unit),
+				}
+			}
+			ExpressionKind::Function(function, arguments) => {
+				write!(f, "{}(", function)?;
+				for (i, argument) in arguments.iter().enumerate() {
+					if i > 0 {
+						write!(f, ", ")?;
+					}
+					write!(f, "{}", argument)?;
+				}
+				write!(f, ")")
+			}
+		}
+	}
+}
+
+/// Writes one side of a binary operation, wrapping it in parentheses if it would otherwise be parsed differently.
+fn write_operand(f: &mut fmt::Formatter<'_>, operand: &Expression, parent: &Operator, rhs: bool) -> fmt::Result {
+	let parenthesize = match &operand.kind {
+		ExpressionKind::Binary(op, _, _) => {
+			op.precedence() < parent.precedence()
+				// Subtraction and division aren't associative, so a - (b - c) must keep its parentheses.
+				|| (rhs && op.precedence() == parent.precedence() && matches!(parent, Operator::Subtract | Operator::Divide))
+		}
+		_ => false,
+	};
+
+	if parenthesize {
+		write!(f, "({})", operand)
+	} else {
+		write!(f, "{}", operand)
+	}
+}
+
+impl Function {
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"calc" => Some(Function::Calc),
+			"min" => Some(Function::Min),
+			"max" => Some(Function::Max),
+			"clamp" => Some(Function::Clamp),
+			"round" => Some(Function::Round),
+			"roundto" => Some(Function::RoundTo),
+			"floor" => Some(Function::Floor),
+			"ceil" => Some(Function::Ceil),
+			"sqrt" => Some(Function::Sqrt),
+			"pow" => Some(Function::Pow),
+			"abs" => Some(Function::Abs),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for Function {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Function::Calc => "calc",
+			Function::Min => "min",
+			Function::Max => "max",
+			Function::Clamp => "clamp",
+			Function::Round => "round",
+			Function::RoundTo => "roundTo",
+			Function::Floor => "floor",
+			Function::Ceil => "ceil",
+			Function::Sqrt => "sqrt",
+			Function::Pow => "pow",
+			Function::Abs => "abs",
+			Function::RoundStep(_) => "round",
+		};
+
+		write!(f, "{}", name)
+	}
+}
+
+impl RoundingStrategy {
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"nearest" => Some(RoundingStrategy::Nearest),
+			"up" => Some(RoundingStrategy::Up),
+			"down" => Some(RoundingStrategy::Down),
+			"to-zero" => Some(RoundingStrategy::ToZero),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for RoundingStrategy {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			RoundingStrategy::Nearest => "nearest",
+			RoundingStrategy::Up => "up",
+			RoundingStrategy::Down => "down",
+			RoundingStrategy::ToZero => "to-zero",
+		};
+
+		write!(f, "{}", name)
+	}
+}
+
+impl Operator {
+	/// Operators with a higher precedence bind more tightly, i.e. `*` and `/` are applied before `+` and `-`, and `^`
+	/// before either.
+	pub fn precedence(&self) -> u8 {
+		match self {
+			Operator::Add | Operator::Subtract => 1,
+			Operator::Multiply | Operator::Divide => 2,
+			Operator::Power => 3,
+		}
+	}
+}
+
+impl fmt::Display for Operator {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let op = match self {
+			Operator::Add => "+",
+			Operator::Subtract => "-",
+			Operator::Multiply => "*",
+			Operator::Divide => "/",
+			Operator::Power => "^",
+		};
+
+		write!(f, "{}", op)
+	}
+}
@@ -0,0 +1,45 @@
+use std::fmt;
+
+use crate::token::Span;
+use crate::tokenize::TokenizationError;
+use crate::validate::ValidationError;
+
+/// Describes why an input could not be parsed as a CSS Math statement.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+	Tokenization(TokenizationError),
+	Validation(ValidationError),
+}
+
+impl Error {
+	/// The location in the input that caused the error.
+	pub fn span(&self) -> Span {
+		match self {
+			Error::Tokenization(e) => e.span(),
+			Error::Validation(e) => e.span(),
+		}
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Tokenization(e) => write!(f, "{}", e),
+			Error::Validation(e) => write!(f, "{}", e),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<TokenizationError> for Error {
+	fn from(error: TokenizationError) -> Self {
+		Error::Tokenization(error)
+	}
+}
+
+impl From<ValidationError> for Error {
+	fn from(error: ValidationError) -> Self {
+		Error::Validation(error)
+	}
+}
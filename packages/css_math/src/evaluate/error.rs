@@ -0,0 +1,10 @@
+use crate::token::Span;
+
+#[derive(Debug, PartialEq)]
+pub enum EvaluationError {
+	DivisionByZero(Span),
+	IncompatibleUnits(Span),
+	/// The result isn't a finite number, i.e. the square root of a negative number, or a value too large for an f64.
+	NonFinite(Span),
+	UnresolvedVariable(Span),
+}
@@ -0,0 +1,248 @@
+use super::error::EvaluationError;
+use crate::ast::{Expression, ExpressionKind, Function, Operator, RoundingStrategy};
+use crate::token::Span;
+use crate::value::Value;
+
+/// Evaluates an expression tree, folding each operation into a single value.
+///
+/// Values are only folded together when their units are compatible, any operation that can't be computed
+/// ahead of time (i.e. `100% - 16px`, or anything containing a `var()`) returns an error so the caller can
+/// fall back to letting the browser compute the value with `calc()`.
+pub(crate) fn evaluator(expression: &Expression) -> Result<Value, EvaluationError> {
+    let value = fold(expression)?;
+
+    // NaN and infinity can't be written as CSS, so they're left for the caller to handle rather than being folded in.
+    if !value.number.is_finite() {
+        return Err(EvaluationError::NonFinite(expression.span));
+    }
+
+    Ok(value)
+}
+
+fn fold(expression: &Expression) -> Result<Value, EvaluationError> {
+    match &expression.kind {
+        ExpressionKind::Number(n) => Ok(Value::new(*n, None)),
+        ExpressionKind::Dimension(n, unit) => Ok(Value::new(*n, Some(unit.clone()))),
+        ExpressionKind::Variable(_) => Err(EvaluationError::UnresolvedVariable(expression.span)),
+        ExpressionKind::Binary(op, lhs, rhs) => {
+            let lhs = evaluator(lhs)?;
+            let rhs = evaluator(rhs)?;
+
+            match op {
+                Operator::Add => add(lhs, rhs, expression.span),
+                Operator::Subtract => subtract(lhs, rhs, expression.span),
+                Operator::Multiply => multiply(lhs, rhs, expression.span),
+                Operator::Divide => divide(lhs, rhs, expression.span),
+                Operator::Power => power(lhs, rhs, expression.span),
+            }
+        }
+        ExpressionKind::Function(function, arguments) => {
+            let arguments = arguments.iter().map(evaluator).collect::<Result<Vec<Value>, _>>()?;
+
+            match function {
+                Function::Calc => Ok(arguments[0].clone()),
+                Function::Min => compare(arguments, f64::min, expression.span),
+                Function::Max => compare(arguments, f64::max, expression.span),
+                // clamp(MIN, VAL, MAX) is equivalent to max(MIN, min(VAL, MAX))
+                Function::Clamp => {
+                    let [min, value, max]: [Value; 3] = arguments
+                        .try_into()
+                        .map_err(|_| EvaluationError::IncompatibleUnits(expression.span))?;
+                    let upper = compare(vec![value, max], f64::min, expression.span)?;
+                    compare(vec![min, upper], f64::max, expression.span)
+                }
+                Function::Round => Ok(map(&arguments[0], round_nearest)),
+                Function::Floor => Ok(map(&arguments[0], f64::floor)),
+                Function::Ceil => Ok(map(&arguments[0], f64::ceil)),
+                Function::Abs => Ok(map(&arguments[0], f64::abs)),
+                Function::Sqrt => power(arguments[0].clone(), Value::new(0.5, None), expression.span),
+                Function::Pow => power(arguments[0].clone(), arguments[1].clone(), expression.span),
+                // roundTo(x, n) rounds x to n decimal places, or to a whole number if n is left out.
+                Function::RoundTo => {
+                    let places = arguments.get(1).map_or(Ok(0.0), |places| match places.unit {
+                        None => Ok(places.number),
+                        Some(_) => Err(EvaluationError::IncompatibleUnits(expression.span)),
+                    })?;
+                    let factor = 10f64.powf(places);
+
+                    Ok(map(&arguments[0], |n| round_nearest(n * factor) / factor))
+                }
+                // round(<strategy>, A, B) rounds A to a multiple of B, a step of 0 gives NaN as it does in CSS.
+                Function::RoundStep(strategy) => {
+                    let step = match arguments.get(1) {
+                        Some(step) => convert(step, &arguments[0].unit, expression.span)?.number,
+                        None => 1.0,
+                    };
+                    let round = match strategy {
+                        RoundingStrategy::Nearest => round_nearest,
+                        RoundingStrategy::Up => f64::ceil,
+                        RoundingStrategy::Down => f64::floor,
+                        RoundingStrategy::ToZero => f64::trunc,
+                    };
+
+                    Ok(map(&arguments[0], |n| round(n / step) * step))
+                }
+            }
+        }
+    }
+}
+
+/// Reduces the arguments of min() or max() to a single value, this is only possible if every argument shares the same unit.
+fn compare(arguments: Vec<Value>, reduce: fn(f64, f64) -> f64, span: Span) -> Result<Value, EvaluationError> {
+    let mut arguments = arguments.into_iter();
+    let mut result = arguments.next().ok_or(EvaluationError::IncompatibleUnits(span))?;
+
+    for argument in arguments {
+        let argument = convert(&argument, &result.unit, span)?;
+        result.number = reduce(result.number, argument.number);
+    }
+
+    Ok(result)
+}
+
+/// Rounds to the nearest whole number as CSS does, with values halfway between two rounded up (towards +∞), i.e.
+/// `-2.5` rounds to `-2` where `f64::round` would give `-3`.
+fn round_nearest(n: f64) -> f64 {
+    (n + 0.5).floor()
+}
+
+/// Applies a function to the number of a value, keeping its unit.
+fn map(value: &Value, f: impl Fn(f64) -> f64) -> Value {
+    Value::new(f(value.number), value.unit.clone())
+}
+
+fn power(lhs: Value, rhs: Value, span: Span) -> Result<Value, EvaluationError> {
+    // Raising a dimension to a power would change its type (i.e. px²), so only plain numbers can be used.
+    if lhs.unit.is_some() || rhs.unit.is_some() {
+        return Err(EvaluationError::IncompatibleUnits(span));
+    }
+
+    Ok(Value::new(lhs.number.powf(rhs.number), None))
+}
+
+/// Converts a value to the given unit so that it can be combined with another value, i.e. `1s + 150ms`
+fn convert(value: &Value, unit: &Option<String>, span: Span) -> Result<Value, EvaluationError> {
+    value.convert(unit).ok_or(EvaluationError::IncompatibleUnits(span))
+}
+
+fn add(lhs: Value, rhs: Value, span: Span) -> Result<Value, EvaluationError> {
+    let rhs = convert(&rhs, &lhs.unit, span)?;
+
+    Ok(Value::new(lhs.number + rhs.number, lhs.unit))
+}
+
+fn subtract(lhs: Value, rhs: Value, span: Span) -> Result<Value, EvaluationError> {
+    let rhs = convert(&rhs, &lhs.unit, span)?;
+
+    Ok(Value::new(lhs.number - rhs.number, lhs.unit))
+}
+
+fn multiply(lhs: Value, rhs: Value, span: Span) -> Result<Value, EvaluationError> {
+    // At least one side of a multiplication must be unitless, the result takes the unit of the other side.
+    match (&lhs.unit, &rhs.unit) {
+        (Some(_), Some(_)) => Err(EvaluationError::IncompatibleUnits(span)),
+        (None, _) => Ok(Value::new(lhs.number * rhs.number, rhs.unit)),
+        (_, None) => Ok(Value::new(lhs.number * rhs.number, lhs.unit)),
+    }
+}
+
+fn divide(lhs: Value, rhs: Value, span: Span) -> Result<Value, EvaluationError> {
+    if rhs.number == 0.0 {
+        return Err(EvaluationError::DivisionByZero(span));
+    }
+
+    // The right hand side of a division must be a number, the validator rejects anything else (i.e. `24px / 16px`)
+    // so we never fold an expression it wouldn't accept.
+    match rhs.unit {
+        None => Ok(Value::new(lhs.number / rhs.number, lhs.unit)),
+        Some(_) => Err(EvaluationError::IncompatibleUnits(span)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+    use crate::tokenize::tokenize;
+    use matches::assert_matches;
+    use test_case::test_case;
+
+    fn evaluate(input: &str) -> Result<Value, EvaluationError> {
+        let tokens = tokenize(input).unwrap();
+        evaluator(&parse(&tokens).unwrap())
+    }
+
+    #[test_case("8px * 2", "16px" ; "multiply px by number")]
+    #[test_case("2 * 8px", "16px" ; "multiply number by px")]
+    #[test_case("1rem + 0.5rem", "1.5rem" ; "add rem to rem")]
+    #[test_case("1.5em - 2em", "-0.5em" ; "subtract em from em")]
+    #[test_case("100% / 3", "33.3333%" ; "divide percent by number")]
+    #[test_case("4 * 2 + 1", "9" ; "unitless")]
+    #[test_case("4px + 2px * 3", "10px" ; "multiplication before addition")]
+    #[test_case("(4px + 2px) * 3", "18px" ; "parentheses before multiplication")]
+    #[test_case("10 - -1", "11" ; "negative number")]
+    #[test_case("((1rem + 1rem) - 0.5rem) * 2", "3rem" ; "double nested")]
+    #[test_case("calc(4px * 2)", "8px" ; "calc")]
+    #[test_case("min(4px, 2px, 3px)", "2px" ; "min")]
+    #[test_case("max(4px, 2px, 3px) * 2", "8px" ; "max")]
+    #[test_case("clamp(1rem, 0.5rem, 3rem)", "1rem" ; "clamp below minimum")]
+    #[test_case("clamp(1rem, 2rem, 3rem)", "2rem" ; "clamp within range")]
+    #[test_case("clamp(1rem, 4rem, 3rem)", "3rem" ; "clamp above maximum")]
+    #[test_case("16px * 1.25 ^ 3", "31.25px" ; "type scale")]
+    #[test_case("2 ^ 3 ^ 2", "512" ; "right associative exponent")]
+    #[test_case("pow(2, 10)", "1024" ; "pow")]
+    #[test_case("sqrt(16) * 1rem", "4rem" ; "sqrt")]
+    #[test_case("roundTo(16px * 1.333 ^ 2, 2)", "28.43px" ; "round to decimal places")]
+    #[test_case("roundTo(2.5)", "3" ; "round to whole number")]
+    #[test_case("round(10.4px)", "10px" ; "round")]
+    #[test_case("round(-2.5px)", "-2px" ; "round negative halfway up")]
+    #[test_case("roundTo(-2.5)", "-2" ; "round to negative halfway up")]
+    #[test_case("floor(10.9px)", "10px" ; "floor")]
+    #[test_case("ceil(10.1px)", "11px" ; "ceil")]
+    #[test_case("abs(-4px)", "4px" ; "abs")]
+    #[test_case("round(up, 2.5, 1)", "3" ; "round up")]
+    #[test_case("round(down, 10.5px, 2px)", "10px" ; "round down to a step")]
+    #[test_case("round(to-zero, -2.5px, 1px)", "-2px" ; "round to zero")]
+    #[test_case("round(nearest, -2.5, 1)", "-2" ; "round halfway up")]
+    #[test_case("round(1.2s, 500ms)", "1s" ; "round to a step in another unit")]
+    #[test_case("1s + 150ms", "1.15s" ; "add milliseconds to seconds")]
+    #[test_case("150ms * 2 - 0.1s", "200ms" ; "subtract seconds from milliseconds")]
+    #[test_case("max(200ms, 0.3s)", "300ms" ; "max of times")]
+    #[test_case("1in - 6px", "0.9375in" ; "absolute lengths")]
+    fn folds(input: &str, expected: &str) {
+        assert_eq!(evaluate(input).unwrap().to_string(), expected);
+    }
+
+    #[test_case("100% - 16px" ; "percent and px")]
+    #[test_case("1rem + 4px" ; "rem and px")]
+    #[test_case("2px * 2px" ; "multiply px by px")]
+    #[test_case("16px / 2rem" ; "divide px by rem")]
+    #[test_case("24px / 16px" ; "divide px by px")]
+    #[test_case("1s / 250ms" ; "divide seconds by milliseconds")]
+    #[test_case("min(100%, 960px)" ; "min of percent and px")]
+    #[test_case("clamp(1rem, 2vw + 1rem, 3rem)" ; "clamp of vw and rem")]
+    fn incompatible_units(input: &str) {
+        assert_matches!(evaluate(input), Err(EvaluationError::IncompatibleUnits(_)));
+    }
+
+    #[test]
+    fn unresolved_variable() {
+        assert_matches!(evaluate("var(--spacing) * 2"), Err(EvaluationError::UnresolvedVariable(_)));
+    }
+
+    #[test_case("(-8) ^ 0.5" ; "exponent of a negative number")]
+    #[test_case("sqrt(-4)" ; "square root of a negative number")]
+    #[test_case("pow(-8, 1 / 3)" ; "pow of a negative number")]
+    #[test_case("1e400 * 1px" ; "overflowing literal")]
+    #[test_case("1e300px * 1e300" ; "overflowing multiplication")]
+    #[test_case("10 ^ 400" ; "overflowing exponent")]
+    #[test_case("round(down, 10px, 0px)" ; "round to a step of zero")]
+    fn non_finite(input: &str) {
+        assert_matches!(evaluate(input), Err(EvaluationError::NonFinite(_)));
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_matches!(evaluate("10px / (2 - 2)"), Err(EvaluationError::DivisionByZero(_)));
+    }
+}
@@ -0,0 +1,18 @@
+mod error;
+mod evaluator;
+pub use error::EvaluationError;
+use evaluator::evaluator;
+
+use crate::ast::Expression;
+use crate::value::Value;
+
+pub(crate) fn evaluate(expression: &Expression) -> Result<Value, EvaluationError> {
+    evaluator(expression)
+}
+
+impl Expression {
+    /// Folds the expression into a single value, see `css_math::evaluate`
+    pub fn evaluate(&self) -> Option<Value> {
+        evaluate(self).ok()
+    }
+}
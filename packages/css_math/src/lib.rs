@@ -1,27 +1,74 @@
-/// Tokenizer and validation checks CSS Math statements (i.e. the string between the parens in calc() expressions)
+/// Tokenizer, validation checks and evaluation of CSS Math statements (i.e. the string between the parens in calc() expressions)
 mod token;
+pub use token::Span;
 
 mod tokenize;
 use tokenize::tokenize;
+pub use tokenize::TokenizationError;
 
 mod validate;
-use validate::validate;
+use validate::{check_types, validate};
+pub use validate::ValidationError;
+
+mod ast;
+pub use ast::{Expression, ExpressionKind, Function, Operator, RoundingStrategy};
+
+mod parse;
+
+mod evaluate;
+
+mod simplify;
+
+mod value;
+pub use value::Value;
+
+mod types;
+pub use types::Type;
+
+mod error;
+pub use error::Error;
+
+/// Parses a CSS Math statement into an expression tree, respecting operator precedence. Every node in the tree
+/// holds the span of the input it was parsed from, and errors report the position of the offending token.
+pub fn parse(input: &str) -> Result<Expression, Error> {
+    let tokens = tokenize(input)?;
+    validate(&tokens)?;
+
+    let expression = parse::parse(&tokens)?;
+    check_types(&expression)?;
+
+    Ok(expression)
+}
 
 pub fn is_css_math(input: &str) -> bool {
-    match tokenize(input) {
-        Ok(tokens) => validate(&tokens),
-        Err(_) => {
-            false
-        }
-    }
+    parse(input).is_ok()
+}
+
+/// Evaluates a CSS Math statement, folding it into a single value when the units involved are compatible
+/// (i.e. `8px * 2` becomes `16px`). Returns `None` if the input is not valid CSS Math, or if it can only be
+/// computed by the browser (i.e. `100% - 16px`, or any statement containing a `var()`), in which case it should
+/// be wrapped in `calc()` instead.
+pub fn evaluate(input: &str) -> Option<Value> {
+    parse(input).ok()?.evaluate()
+}
+
+/// Formats a CSS Math statement as the shortest valid CSS value, folding everything that can be computed ahead of
+/// time and only wrapping what's left in `calc()` when needed, see `Expression::to_css`
+pub fn format(input: &str) -> Result<String, Error> {
+    Ok(parse(input)?.to_css()?)
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 	use test_case::test_case;
 
 	#[test_case("var(--typescale-3) * 1.5")]
+	#[test_case("clamp(1rem, 2vw + 1rem, 3rem)")]
+	#[test_case("min(100%, 960px)")]
+	#[test_case("calc(100% - 16px) * 2")]
+	#[test_case("var(--space_sm, 4px) * 2")]
 	fn valid(input: &str) {
 		assert_eq!(true, is_css_math(input))
 	}
@@ -29,8 +76,100 @@ mod tests {
 	#[test_case("12px px" ; "consecutive units without a number")]
 	#[test_case("2% rem" ; "consecutive units without a number #2")]
 	#[test_case("2px rem px" ; "consecutive units without a number #3")]
+	#[test_case("10px + 2s" ; "length and time")]
+	#[test_case("45deg - 4px" ; "angle and length")]
 	#[test_case("15. * 2px" ; "invalid number")]
 	fn invalid(input: &str) {
 		assert_eq!(false, is_css_math(input))
 	}
+
+	#[test_case("8px * 2", "16px")]
+	#[test_case("1rem + 0.5rem * 2", "2rem")]
+	#[test_case("(100% - 20%) / 2", "40%")]
+	#[test_case("min(16px, 4px * 8) + 2px", "18px")]
+	#[test_case("clamp(1rem, 4rem, 3rem)", "3rem")]
+	#[test_case("calc(8px * 2)", "16px")]
+	#[test_case(".5rem * 2", "1rem")]
+	#[test_case("1e3px / 10", "100px")]
+	#[test_case("+4px + 2px", "6px")]
+	#[test_case("16px * 1.25 ^ 3", "31.25px")]
+	#[test_case("roundTo(16 * 1.2 ^ 2, 1) * 1px", "23px")]
+	fn evaluates(input: &str, expected: &str) {
+		assert_eq!(evaluate(input).unwrap().to_string(), expected)
+	}
+
+	#[test_case("100% - 16px" ; "incompatible units")]
+	#[test_case("var(--spacing-base) * 2" ; "variable")]
+	#[test_case("min(100%, 960px)" ; "function with incompatible units")]
+	#[test_case("12px px" ; "invalid statement")]
+	#[test_case("(-8) ^ 0.5" ; "not a number")]
+	#[test_case("1e400 * 1px" ; "infinite")]
+	fn does_not_evaluate(input: &str) {
+		assert!(evaluate(input).is_none())
+	}
+
+	#[test]
+	fn parses() {
+		let expression = parse("var(--spacing) * 2").unwrap();
+
+		assert_eq!(expression.span, Span::new(0, 18));
+		assert!(matches!(expression.kind, ExpressionKind::Binary(Operator::Multiply, _, _)));
+	}
+
+	#[test_case("100%-16px", "100% - 16px" ; "subtraction without whitespace")]
+	#[test_case("var(--a)*var(--b)", "var(--a) * var(--b)" ; "multiplication without whitespace")]
+	#[test_case("var(--a)* 2", "var(--a) * 2" ; "whitespace on one side")]
+	#[test_case("(1px+2px)*3", "(1px + 2px) * 3" ; "parentheses that change precedence are kept")]
+	#[test_case("10px-(2px-1px)", "10px - (2px - 1px)" ; "parentheses in subtraction are kept")]
+	#[test_case("((1px))+(2px*3)", "1px + 2px * 3" ; "redundant parentheses are removed")]
+	#[test_case("clamp(1rem,2vw+1rem,3rem)", "clamp(1rem, 2vw + 1rem, 3rem)" ; "function arguments")]
+	#[test_case("var(--base)*var(--ratio)^3", "var(--base) * pow(var(--ratio), 3)" ; "exponent as pow")]
+	#[test_case("floor(var(--a) * 1px)", "round(down, var(--a) * 1px, 1px)" ; "floor as round")]
+	#[test_case("roundTo(var(--a) * 1rem, 2)", "round(nearest, var(--a) * 1rem, 0.01rem)" ; "round to as round")]
+	#[test_case("roundTo(var(--a), var(--b))", "roundTo(var(--a), var(--b))" ; "round to of unknown unit")]
+	#[test_case("round(var(--a) / 2)", "round(var(--a) / 2)" ; "round of unknown unit")]
+	#[test_case("roundTo(2.5px * var(--a), var(--b))", "round(nearest, 2.5px * var(--a), pow(0.1, var(--b)) * 1px)" ; "round to variable places")]
+	#[test_case("round(up,2.5,1)", "round(up, 2.5, 1)" ; "rounding strategy")]
+	#[test_case("ROUND(To-Zero, var(--a), 1px)", "round(to-zero, var(--a), 1px)" ; "uppercase rounding strategy")]
+	fn prints(input: &str, expected: &str) {
+		assert_eq!(parse(input).unwrap().to_string(), expected);
+	}
+
+	#[test_case("2 * 4px", "8px" ; "constant")]
+	#[test_case("(var(--a))+(2px*2)", "calc(var(--a) + 4px)" ; "variable")]
+	#[test_case("calc(min(1px, 2rem))", "min(1px, 2rem)" ; "redundant calc")]
+	#[test_case("calc(var(--a) * 1)", "var(--a)" ; "single variable")]
+	#[test_case("var(--ratio) ^ 2", "pow(var(--ratio), 2)" ; "exponent")]
+	#[test_case("(-8) ^ 0.5", "pow(-8, 0.5)" ; "exponent that isn't a number is left to the browser")]
+	#[test_case("round(up, 2.5, 1)", "3" ; "rounding strategy")]
+	#[test_case("ceil(var(--a) * 1px)", "round(up, var(--a) * 1px, 1px)" ; "tokens studio rounding")]
+	#[test_case("var(--a) * 1.23456", "calc(var(--a) * 1.23456)" ; "literal precision")]
+	fn formats(input: &str, expected: &str) {
+		assert_eq!(format(input).unwrap(), expected);
+	}
+
+	#[test_case("10px / (2 - 2)" ; "constant")]
+	#[test_case("var(--a) / (1 - 1)" ; "variable")]
+	fn does_not_format_division_by_zero(input: &str) {
+		assert!(matches!(format(input), Err(Error::Validation(ValidationError::DivisionByZero(_)))));
+	}
+
+	#[test]
+	fn does_not_format_rounding_of_unknown_unit() {
+		let error = format("floor(var(--a)) * 2").unwrap_err();
+
+		assert_eq!(error.to_string(), "cannot round a value of unknown unit");
+	}
+
+	#[test_case("16px / 2px", 9, "division by a length" ; "division by unit")]
+	#[test_case("10px + 2s", 1, "cannot combine a length with a time" ; "incompatible types")]
+	#[test_case("4px * 2pixels", 8, "unknown unit" ; "unknown unit")]
+	#[test_case("4px & 2", 5, "unrecognized character '&'" ; "unrecognized character")]
+	#[test_case("(4px + 2", 1, "mismatched parentheses" ; "mismatched parentheses")]
+	fn reports_errors(input: &str, column: usize, message: &str) {
+		let error = parse(input).unwrap_err();
+
+		assert_eq!(error.span().column(), column);
+		assert_eq!(error.to_string(), message);
+	}
 }
@@ -0,0 +1,10 @@
+mod parser;
+use parser::Parser;
+
+use crate::ast::Expression;
+use crate::token::{Span, Token};
+use crate::validate::ValidationError;
+
+pub(crate) fn parse(tokens: &[(Token, Span)]) -> Result<Expression, ValidationError> {
+    Parser::new(tokens).parse()
+}
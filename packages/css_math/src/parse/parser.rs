@@ -0,0 +1,262 @@
+use std::iter::Peekable;
+use std::slice::Iter;
+
+use crate::ast::{Expression, ExpressionKind, Function, Operator, RoundingStrategy};
+use crate::token::{Span, Token};
+use crate::validate::ValidationError;
+
+/// Builds an `Expression` tree from a stream of tokens using recursive descent, so that multiplication
+/// and division bind tighter than addition and subtraction, and exponents tighter still. Operators of the same
+/// precedence are left associative, i.e. `10px - 2px - 1px` is parsed as `(10px - 2px) - 1px`, apart from `^`
+/// which is right associative, i.e. `2 ^ 3 ^ 2` is parsed as `2 ^ (3 ^ 2)`.
+pub(crate) struct Parser<'a> {
+    tokens: Peekable<Iter<'a, (Token, Span)>>,
+    /// The span of the last token consumed, used to report errors at the end of the input.
+    last: Span,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(tokens: &'a [(Token, Span)]) -> Self {
+        Parser {
+            tokens: tokens.iter().peekable(),
+            last: Span::new(0, 0),
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<Expression, ValidationError> {
+        let expression = self.process_sum()?;
+
+        match self.tokens.next() {
+            Some((_, span)) => Err(ValidationError::InvalidSyntax(*span)),
+            None => Ok(expression),
+        }
+    }
+
+    fn next(&mut self) -> Option<&'a (Token, Span)> {
+        let next = self.tokens.next();
+        if let Some((_, span)) = next {
+            self.last = *span;
+        }
+        next
+    }
+
+    /// Consumes the next token if it is one of the given operators.
+    fn next_operator(&mut self, operators: &[Operator]) -> Option<Operator> {
+        let operator = match self.tokens.peek() {
+            Some((Token::Operator(op), _)) => match op.as_str() {
+                "+" => Operator::Add,
+                "-" => Operator::Subtract,
+                "*" => Operator::Multiply,
+                "/" => Operator::Divide,
+                "^" => Operator::Power,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        if operators.contains(&operator) {
+            self.next();
+            Some(operator)
+        } else {
+            None
+        }
+    }
+
+    /// Handles addition and subtraction, the lowest precedence operations.
+    fn process_sum(&mut self) -> Result<Expression, ValidationError> {
+        let mut lhs = self.process_product()?;
+
+        while let Some(op) = self.next_operator(&[Operator::Add, Operator::Subtract]) {
+            let rhs = self.process_product()?;
+            lhs = binary(op, lhs, rhs);
+        }
+
+        Ok(lhs)
+    }
+
+    /// Handles multiplication and division.
+    fn process_product(&mut self) -> Result<Expression, ValidationError> {
+        let mut lhs = self.process_power()?;
+
+        while let Some(op) = self.next_operator(&[Operator::Multiply, Operator::Divide]) {
+            let rhs = self.process_power()?;
+            lhs = binary(op, lhs, rhs);
+        }
+
+        Ok(lhs)
+    }
+
+    /// Handles exponentiation, the highest precedence operation.
+    fn process_power(&mut self) -> Result<Expression, ValidationError> {
+        let lhs = self.process_operand()?;
+
+        match self.next_operator(&[Operator::Power]) {
+            Some(op) => {
+                let rhs = self.process_power()?;
+                Ok(binary(op, lhs, rhs))
+            }
+            None => Ok(lhs),
+        }
+    }
+
+    /// Handles a single number (with an optional unit), a variable, a math function or a parenthesized expression.
+    fn process_operand(&mut self) -> Result<Expression, ValidationError> {
+        match self.next() {
+            Some((Token::Number(num), span)) => {
+                let number = num
+                    .parse::<f64>()
+                    .map_err(|_| ValidationError::InvalidNumber(*span))?;
+
+                match self.tokens.peek() {
+                    Some((Token::Unit(unit), unit_span)) => {
+                        let unit_span = *unit_span;
+                        self.next();
+                        Ok(Expression::new(
+                            ExpressionKind::Dimension(number, unit.clone()),
+                            span.to(unit_span),
+                        ))
+                    }
+                    _ => Ok(Expression::new(ExpressionKind::Number(number), *span)),
+                }
+            }
+            Some((Token::Variable(var), span)) => {
+                Ok(Expression::new(ExpressionKind::Variable(var.clone()), *span))
+            }
+            Some((Token::Function(name), span)) => {
+                let function = match (Function::from_name(name), self.process_strategy()?) {
+                    (Some(Function::Round), Some(strategy)) => Function::RoundStep(strategy),
+                    (Some(function), None) => function,
+                    _ => return Err(ValidationError::InvalidSyntax(*span)),
+                };
+                let mut arguments = vec![self.process_sum()?];
+
+                loop {
+                    match self.next() {
+                        Some((Token::Comma, _)) => arguments.push(self.process_sum()?),
+                        Some((Token::RightParen, end)) => {
+                            let function = match function {
+                                Function::Round | Function::RoundStep(_) if arguments.len() > 2 => {
+                                    return Err(ValidationError::InvalidArgumentCount(*end));
+                                }
+                                // round(A, B) is CSS round() with the default strategy, only round(A) is Tokens Studio's.
+                                Function::Round if arguments.len() == 2 => Function::RoundStep(RoundingStrategy::Nearest),
+                                function => function,
+                            };
+
+                            return Ok(Expression::new(ExpressionKind::Function(function, arguments), span.to(*end)));
+                        }
+                        Some((_, span)) => return Err(ValidationError::InvalidSyntax(*span)),
+                        None => return Err(ValidationError::MismatchedParentheses(self.last)),
+                    }
+                }
+            }
+            Some((Token::LeftParen, start)) => {
+                let start = *start;
+                let mut expression = self.process_sum()?;
+
+                // The parentheses are part of the sub-expression, so errors about it point at the opening paren.
+                match self.next() {
+                    Some((Token::RightParen, end)) => {
+                        expression.span = start.to(*end);
+                        Ok(expression)
+                    }
+                    Some((_, span)) => Err(ValidationError::InvalidSyntax(*span)),
+                    None => Err(ValidationError::MismatchedParentheses(self.last)),
+                }
+            }
+            Some((_, span)) => Err(ValidationError::InvalidSyntax(*span)),
+            None => Err(ValidationError::IncompleteExpression(self.last)),
+        }
+    }
+
+    /// Handles the rounding strategy that may be passed as the first argument of round() (i.e. `round(down, 10.5px,
+    /// 1px)`), along with the comma that follows it.
+    fn process_strategy(&mut self) -> Result<Option<RoundingStrategy>, ValidationError> {
+        let strategy = match self.tokens.peek() {
+            Some((Token::Keyword(keyword), span)) => {
+                RoundingStrategy::from_name(keyword).ok_or(ValidationError::InvalidSyntax(*span))?
+            }
+            _ => return Ok(None),
+        };
+        self.next();
+
+        match self.next() {
+            Some((Token::Comma, _)) => Ok(Some(strategy)),
+            Some((_, span)) => Err(ValidationError::InvalidSyntax(*span)),
+            None => Err(ValidationError::IncompleteExpression(self.last)),
+        }
+    }
+}
+
+fn binary(op: Operator, lhs: Expression, rhs: Expression) -> Expression {
+    let span = lhs.span.to(rhs.span);
+    Expression::new(ExpressionKind::Binary(op, Box::new(lhs), Box::new(rhs)), span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenize::tokenize;
+    use matches::assert_matches;
+    use test_case::test_case;
+
+    fn parse(input: &str) -> Result<Expression, ValidationError> {
+        let tokens = tokenize(input).unwrap();
+        Parser::new(&tokens).parse()
+    }
+
+    /// Prints the tree with every operation parenthesized, so we can check the structure of the tree in our tests.
+    fn print(expression: &Expression) -> String {
+        match &expression.kind {
+            ExpressionKind::Number(n) => n.to_string(),
+            ExpressionKind::Dimension(n, unit) => format!("{}{}", n, unit),
+            ExpressionKind::Variable(var) => var.clone(),
+            ExpressionKind::Binary(op, lhs, rhs) => format!("({} {} {})", print(lhs), op, print(rhs)),
+            ExpressionKind::Function(function, arguments) => format!(
+                "{}({})",
+                function,
+                arguments.iter().map(print).collect::<Vec<String>>().join(", ")
+            ),
+        }
+    }
+
+    #[test_case("1 + 2 * 3", "(1 + (2 * 3))" ; "multiplication before addition")]
+    #[test_case("1 * 2 + 3", "((1 * 2) + 3)" ; "multiplication before addition on the left")]
+    #[test_case("(1 + 2) * 3", "((1 + 2) * 3)" ; "parentheses")]
+    #[test_case("10px - 2px - 1px", "((10px - 2px) - 1px)" ; "left associative subtraction")]
+    #[test_case("100% / 2 / 2", "((100% / 2) / 2)" ; "left associative division")]
+    #[test_case("var(--gap) * -1", "(var(--gap) * -1)" ; "variable and negative number")]
+    #[test_case("clamp(1rem, 2vw + 1rem, 3rem)", "clamp(1rem, (2vw + 1rem), 3rem)" ; "clamp")]
+    #[test_case("min(100%, 960px) - 2 * 8px", "(min(100%, 960px) - (2 * 8px))" ; "function as an operand")]
+    #[test_case("calc(max(1px, 2px) * 2)", "calc((max(1px, 2px) * 2))" ; "nested functions")]
+    #[test_case("16px * 1.25 ^ 3", "(16px * (1.25 ^ 3))" ; "exponent before multiplication")]
+    #[test_case("2 ^ 3 ^ 2", "(2 ^ (3 ^ 2))" ; "right associative exponent")]
+    #[test_case("roundTo(1.2345, 2)", "roundTo(1.2345, 2)" ; "tokens studio function")]
+    #[test_case("round(up, 2.5 * 2, 1)", "round((2.5 * 2), 1)" ; "rounding strategy")]
+    fn precedence(input: &str, expected: &str) {
+        assert_eq!(print(&parse(input).unwrap()), expected);
+    }
+
+    #[test]
+    fn spans() {
+        let expression = parse("(8px + 4px) * 2").unwrap();
+        assert_eq!(expression.span, Span::new(0, 15));
+
+        match expression.kind {
+            ExpressionKind::Binary(Operator::Multiply, lhs, rhs) => {
+                assert_eq!(lhs.span, Span::new(0, 11));
+                assert_eq!(rhs.span, Span::new(14, 15));
+            }
+            kind => panic!("Unexpected expression {:?}", kind),
+        }
+    }
+
+    #[test_case("1 +" ; "trailing operator")]
+    #[test_case("(1 + 2" ; "missing closing paren")]
+    #[test_case("1 2" ; "missing operator")]
+    #[test_case("px + 2" ; "unit without a number")]
+    #[test_case("min(1px, 2px" ; "unclosed function")]
+    fn invalid(input: &str) {
+        assert_matches!(parse(input), Err(_));
+    }
+}
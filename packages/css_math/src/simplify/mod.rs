@@ -0,0 +1,54 @@
+mod simplifier;
+use simplifier::simplifier;
+
+use crate::ast::{Expression, ExpressionKind, Function, Operator};
+use crate::validate::ValidationError;
+
+impl Expression {
+    /// Returns an equivalent expression with every constant sub-expression folded into a single value (i.e.
+    /// `var(--gap) * 2 * 4px` becomes `var(--gap) * 8px`), redundant `calc()` calls removed, and multiplication or
+    /// division by 1 dropped. References to `var()` are left as they are.
+    pub fn simplify(&self) -> Expression {
+        simplifier(self)
+    }
+
+    /// Prints the simplified expression as the shortest valid CSS value, only wrapping it in `calc()` when that is
+    /// needed, i.e. `2 * 4px` becomes `8px`, `calc(min(1px, 2rem))` becomes `min(1px, 2rem)`, and
+    /// `(var(--a))+(2px*2)` becomes `calc(var(--a) + 4px)`
+    ///
+    /// Fails if a divisor folds to zero (i.e. `10px / (2 - 2)`), or if the expression rounds a value with a Tokens
+    /// Studio function whose unit can't be known ahead of time (i.e. `floor(var(--a))`), as CSS round() needs a step
+    /// with the same unit.
+    pub fn to_css(&self) -> Result<String, ValidationError> {
+        let expression = self.simplify();
+
+        if let Some(error) = unprintable(&expression) {
+            return Err(error);
+        }
+
+        Ok(match expression.kind {
+            // Exponents are printed as pow(), which doesn't need to be wrapped.
+            ExpressionKind::Binary(op, _, _) if op != Operator::Power => format!("calc({})", expression),
+            _ => expression.to_string(),
+        })
+    }
+}
+
+/// Finds the first part of a simplified expression that can't be written as CSS, see `Expression::to_css`
+fn unprintable(expression: &Expression) -> Option<ValidationError> {
+    match &expression.kind {
+        ExpressionKind::Binary(Operator::Divide, _, rhs)
+            if matches!(rhs.kind, ExpressionKind::Number(n) | ExpressionKind::Dimension(n, _) if n == 0.0) =>
+        {
+            Some(ValidationError::DivisionByZero(rhs.span))
+        }
+        ExpressionKind::Function(Function::Round | Function::Floor | Function::Ceil | Function::RoundTo, arguments)
+            if arguments[0].unit().is_none() =>
+        {
+            Some(ValidationError::UnknownRoundingUnit(expression.span))
+        }
+        ExpressionKind::Function(_, arguments) => arguments.iter().find_map(unprintable),
+        ExpressionKind::Binary(_, lhs, rhs) => unprintable(lhs).or_else(|| unprintable(rhs)),
+        _ => None,
+    }
+}
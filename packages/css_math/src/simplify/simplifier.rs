@@ -0,0 +1,203 @@
+use crate::ast::{Expression, ExpressionKind, Function, Operator};
+use crate::evaluate::evaluate;
+use crate::token::Span;
+use crate::value::Value;
+
+/// Rebuilds the expression tree bottom up, folding every sub-expression that can be computed ahead of time into
+/// a single value.
+pub(crate) fn simplifier(expression: &Expression) -> Expression {
+    let span = expression.span;
+
+    let simplified = match &expression.kind {
+        ExpressionKind::Number(_) | ExpressionKind::Dimension(_, _) | ExpressionKind::Variable(_) => {
+            return expression.clone();
+        }
+        // Constant sub-expressions are folded from the tree as it was written, so rounding never compounds.
+        _ if evaluate(expression).is_ok() => return fold(expression.clone()),
+        // Nested calc() calls are redundant, the parentheses needed to keep the structure of the tree are
+        // added back when printing.
+        ExpressionKind::Function(Function::Calc, arguments) => return simplifier(&arguments[0]),
+        ExpressionKind::Function(function @ (Function::Min | Function::Max), arguments) => {
+            let arguments = merge_arguments(*function, arguments.iter().map(simplifier).collect());
+
+            // min() or max() of a single value is just that value.
+            if arguments.len() == 1 {
+                return arguments.into_iter().next().unwrap();
+            }
+
+            Expression::new(ExpressionKind::Function(*function, arguments), span)
+        }
+        ExpressionKind::Function(function, arguments) => Expression::new(
+            ExpressionKind::Function(*function, arguments.iter().map(simplifier).collect()),
+            span,
+        ),
+        ExpressionKind::Binary(op, lhs, rhs) => binary(*op, simplifier(lhs), simplifier(rhs), span),
+    };
+
+    fold(simplified)
+}
+
+/// Replaces the expression with its value, if it can be computed. Folded values are rounded the same way computed
+/// values are printed, literals that aren't folded are kept as written.
+fn fold(expression: Expression) -> Expression {
+    match evaluate(&expression) {
+        Ok(value) => literal(value.rounded(), expression.span),
+        Err(_) => expression,
+    }
+}
+
+fn literal(value: Value, span: Span) -> Expression {
+    match value.unit {
+        Some(unit) => Expression::new(ExpressionKind::Dimension(value.number, unit), span),
+        None => Expression::new(ExpressionKind::Number(value.number), span),
+    }
+}
+
+/// The value of a number or dimension, without evaluating anything.
+fn value(expression: &Expression) -> Option<Value> {
+    match &expression.kind {
+        ExpressionKind::Number(n) => Some(Value::new(*n, None)),
+        ExpressionKind::Dimension(n, unit) => Some(Value::new(*n, Some(unit.clone()))),
+        _ => None,
+    }
+}
+
+/// Folds the constant operands of a binary operation into each other where the operation allows it, even if the
+/// operation as a whole can't be computed, i.e. `var(--a) * 2 * 4` becomes `var(--a) * 8`
+fn binary(op: Operator, lhs: Expression, rhs: Expression, span: Span) -> Expression {
+    match (op, value(&lhs), value(&rhs)) {
+        // Multiplying or dividing by 1 does nothing.
+        (Operator::Multiply | Operator::Divide, _, Some(Value { number: 1.0, unit: None })) => lhs,
+        (Operator::Multiply, Some(Value { number: 1.0, unit: None }), _) => rhs,
+        (Operator::Multiply, None, Some(constant)) => scale(lhs, constant, Operator::Multiply, span),
+        (Operator::Multiply, Some(constant), None) => scale(rhs, constant, Operator::Multiply, span),
+        (Operator::Divide, None, Some(constant)) => scale(lhs, constant, Operator::Divide, span),
+        (Operator::Add | Operator::Subtract, None, Some(constant)) => offset(lhs, op, constant, span),
+        _ => build(op, lhs, rhs, span),
+    }
+}
+
+/// Combines `(x * a) * b` into `x * (a * b)`, and `(x * a) / b` into `x * (a / b)`
+fn scale(operand: Expression, constant: Value, op: Operator, span: Span) -> Expression {
+    if let ExpressionKind::Binary(Operator::Multiply, inner_lhs, inner_rhs) = &operand.kind {
+        let (x, factor) = match (value(inner_lhs), value(inner_rhs)) {
+            (None, Some(factor)) => (inner_lhs, factor),
+            (Some(factor), None) => (inner_rhs, factor),
+            _ => return build(op, operand, literal(constant, span), span),
+        };
+
+        let combined = build(op, literal(factor, span), literal(constant.clone(), span), span);
+
+        if let Ok(combined) = evaluate(&combined) {
+            return binary(Operator::Multiply, (**x).clone(), literal(combined.rounded(), span), span);
+        }
+    }
+
+    build(op, operand, literal(constant, span), span)
+}
+
+/// Combines `(x + a) - b` into `x + (a - b)`, keeping the sign of the result in the operator so that we never
+/// print `x + -2px`
+fn offset(operand: Expression, op: Operator, constant: Value, span: Span) -> Expression {
+    let constant = signed(op, constant);
+
+    if let ExpressionKind::Binary(inner @ (Operator::Add | Operator::Subtract), x, inner_rhs) = &operand.kind {
+        if let Some(inner_constant) = value(inner_rhs) {
+            let inner_constant = signed(*inner, inner_constant);
+
+            if inner_constant.unit == constant.unit {
+                let combined = Value::new(inner_constant.number + constant.number, constant.unit).rounded();
+                return unsigned((**x).clone(), combined, span);
+            }
+        }
+    }
+
+    unsigned(operand, constant, span)
+}
+
+/// The value added by an addition or subtraction, i.e. `-2px` for `x - 2px`
+fn signed(op: Operator, value: Value) -> Value {
+    match op {
+        Operator::Subtract => Value::new(-value.number, value.unit),
+        _ => value,
+    }
+}
+
+/// Builds `x + value`, or `x - value` if the value is negative.
+fn unsigned(operand: Expression, value: Value, span: Span) -> Expression {
+    if value.number < 0.0 {
+        build(Operator::Subtract, operand, literal(Value::new(-value.number, value.unit), span), span)
+    } else {
+        build(Operator::Add, operand, literal(value, span), span)
+    }
+}
+
+fn build(op: Operator, lhs: Expression, rhs: Expression, span: Span) -> Expression {
+    Expression::new(ExpressionKind::Binary(op, Box::new(lhs), Box::new(rhs)), span)
+}
+
+/// Folds the constant arguments of min() or max() that share a unit into a single argument, i.e.
+/// `min(var(--a), 4px, 2px)` becomes `min(var(--a), 2px)`
+fn merge_arguments(function: Function, arguments: Vec<Expression>) -> Vec<Expression> {
+    let reduce = match function {
+        Function::Min => f64::min,
+        _ => f64::max,
+    };
+
+    let mut merged: Vec<Expression> = Vec::new();
+
+    for argument in arguments {
+        let existing = value(&argument).and_then(|constant| {
+            merged.iter_mut().find_map(|other| match (&mut other.kind, &constant.unit) {
+                (ExpressionKind::Number(n), None) => Some((n, constant.number)),
+                (ExpressionKind::Dimension(n, unit), Some(u)) if unit == u => Some((n, constant.number)),
+                _ => None,
+            })
+        });
+
+        match existing {
+            Some((n, number)) => *n = reduce(*n, number),
+            None => merged.push(argument),
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+    use crate::tokenize::tokenize;
+    use test_case::test_case;
+
+    fn simplify(input: &str) -> String {
+        let tokens = tokenize(input).unwrap();
+        simplifier(&parse(&tokens).unwrap()).to_string()
+    }
+
+    #[test_case("2 * 4px", "8px" ; "constant expression")]
+    #[test_case("var(--a) + 2 * 4px", "var(--a) + 8px" ; "constant sub-expression")]
+    #[test_case("var(--a) * 2 * 4", "var(--a) * 8" ; "chained multiplication")]
+    #[test_case("2 * var(--a) * 4", "var(--a) * 8" ; "chained multiplication around a variable")]
+    #[test_case("var(--a) * 2 / 4", "var(--a) * 0.5" ; "multiplication then division")]
+    #[test_case("var(--a) + 4px - 6px", "var(--a) - 2px" ; "chained addition")]
+    #[test_case("var(--a) - 2px - 2px", "var(--a) - 4px" ; "chained subtraction")]
+    #[test_case("var(--a) + 1rem - 2px", "var(--a) + 1rem - 2px" ; "chained addition with different units")]
+    #[test_case("var(--a) * 1", "var(--a)" ; "multiplication by one")]
+    #[test_case("1 * var(--a) / 1", "var(--a)" ; "division by one")]
+    #[test_case("calc(var(--a) * 2) - calc(2px)", "var(--a) * 2 - 2px" ; "nested calc")]
+    #[test_case("min(var(--a), 4px, 2px)", "min(var(--a), 2px)" ; "min arguments")]
+    #[test_case("max(4px, 2px, 3px)", "4px" ; "max of constants")]
+    #[test_case("min(var(--a))", "var(--a)" ; "min of a single argument")]
+    #[test_case("clamp(1rem, 2vw + 1rem, 3rem)", "clamp(1rem, 2vw + 1rem, 3rem)" ; "clamp")]
+    #[test_case("100% / 3", "33.3333%" ; "rounds folded values")]
+    #[test_case("var(--a) * 1.23456", "var(--a) * 1.23456" ; "keeps literals as written")]
+    #[test_case("var(--a) * 1.23456 * 2", "var(--a) * 2.4691" ; "rounds folded literals")]
+    #[test_case("var(--a) + 16px * 1.25 ^ 3", "var(--a) + 31.25px" ; "rounds once when folding")]
+    #[test_case("10px / (2 - 2)", "10px / 0" ; "keeps division by a divisor that folds to zero")]
+    #[test_case("var(--a) * (1 + 2) ^ 2", "var(--a) * 9" ; "exponent")]
+    fn simplifies(input: &str, expected: &str) {
+        assert_eq!(simplify(input), expected);
+    }
+}
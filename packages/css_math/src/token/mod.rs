@@ -1,9 +1,37 @@
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Token {
 	Number(String),
 	Unit(String),
 	Variable(String),
 	Operator(String),
+	/// A CSS math function, including its opening parenthesis (i.e. `min(`)
+	Function(String),
+	/// A keyword argument of a math function, i.e. the rounding strategy in `round(down, 10.5px, 1px)`
+	Keyword(String),
+	Comma,
 	LeftParen,
 	RightParen,
-}
\ No newline at end of file
+}
+
+/// The location of a token or expression within the original input, as a range of character offsets.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+	pub start: usize,
+	pub end: usize,
+}
+
+impl Span {
+	pub fn new(start: usize, end: usize) -> Self {
+		Span { start, end }
+	}
+
+	/// The 1-based column the span starts at, for use in error messages.
+	pub fn column(&self) -> usize {
+		self.start + 1
+	}
+
+	/// Creates a new span covering both self and other.
+	pub fn to(&self, other: Span) -> Span {
+		Span::new(self.start.min(other.start), self.end.max(other.end))
+	}
+}
@@ -1,9 +1,33 @@
-use crate::token::Token;
+use std::fmt;
 
-#[allow(dead_code)]
-#[derive(Debug)]
+use crate::token::Span;
+
+#[derive(Debug, PartialEq)]
 pub enum TokenizationError {
-	UnrecognizedToken(Token),
-	UnrecognizedCharacter(char),
-	InvalidVariable(String)
-}
\ No newline at end of file
+	UnrecognizedCharacter(char, Span),
+	UnrecognizedFunction(String, Span),
+	InvalidVariable(String, Span)
+}
+
+impl TokenizationError {
+	/// The location of the offending characters in the input.
+	pub fn span(&self) -> Span {
+		match self {
+			TokenizationError::UnrecognizedCharacter(_, span) => *span,
+			TokenizationError::UnrecognizedFunction(_, span) => *span,
+			TokenizationError::InvalidVariable(_, span) => *span,
+		}
+	}
+}
+
+impl fmt::Display for TokenizationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TokenizationError::UnrecognizedCharacter(c, _) => write!(f, "unrecognized character '{}'", c),
+			TokenizationError::UnrecognizedFunction(name, _) => write!(f, "unrecognized function '{}()'", name),
+			TokenizationError::InvalidVariable(variable, _) => write!(f, "invalid variable '{}'", variable),
+		}
+	}
+}
+
+impl std::error::Error for TokenizationError {}
@@ -1,14 +1,14 @@
-use crate::token::Token;
+use crate::token::{Span, Token};
 
 mod tokenizer;
 use tokenizer::Tokenizer;
 mod error;
-use error::TokenizationError;
+pub use error::TokenizationError;
 
 
-pub(crate) fn tokenize(input: &str) -> Result<Vec<Token>, TokenizationError> {
+pub(crate) fn tokenize(input: &str) -> Result<Vec<(Token, Span)>, TokenizationError> {
     let tokenizer = Tokenizer::new(input);
-    let tokens: Result<Vec<Token>, _> = tokenizer.collect();
+    let tokens: Result<Vec<(Token, Span)>, _> = tokenizer.collect();
     tokens
 }
 
@@ -22,7 +22,10 @@ mod tests {
 	#[test_case("(2 * 10ch) + 4px", vec![Token::LeftParen,Token::Number(String::from("2")),Token::Operator(String::from("*")),Token::Number(String::from("10")),Token::Unit(String::from("ch")),Token::RightParen,Token::Operator(String::from("+")),Token::Number(String::from("4")),Token::Unit(String::from("px"))] ; "(2 * 10ch) + 4px")]
 	#[test_case("10 - -1", vec![Token::Number(String::from("10")),Token::Operator(String::from("-")),Token::Number(String::from("-1"))] ; "10 - -1")]
 	#[test_case("(400 / -23) * 1", vec![Token::LeftParen,Token::Number(String::from("400")),Token::Operator(String::from("/")),Token::Number(String::from("-23")),Token::RightParen,Token::Operator(String::from("*")),Token::Number(String::from("1"))] ; "(400 / -23) * 1")]
+	#[test_case("clamp(1rem, 2vw + 1rem, 3rem)", vec![Token::Function(String::from("clamp")),Token::Number(String::from("1")),Token::Unit(String::from("rem")),Token::Comma,Token::Number(String::from("2")),Token::Unit(String::from("vw")),Token::Operator(String::from("+")),Token::Number(String::from("1")),Token::Unit(String::from("rem")),Token::Comma,Token::Number(String::from("3")),Token::Unit(String::from("rem")),Token::RightParen] ; "clamp(1rem, 2vw + 1rem, 3rem)")]
+	#[test_case("calc(var(--gap) * 2)", vec![Token::Function(String::from("calc")),Token::Variable(String::from("var(--gap)")),Token::Operator(String::from("*")),Token::Number(String::from("2")),Token::RightParen] ; "calc(var(--gap) * 2)")]
 	fn test_output(input: &str, expected: Vec<Token>) {
-		assert_eq!(tokenize(input).unwrap(), expected);
+		let tokens: Vec<Token> = tokenize(input).unwrap().into_iter().map(|(token, _)| token).collect();
+		assert_eq!(tokens, expected);
 	}
 }
@@ -1,113 +1,320 @@
-use std::iter::Peekable;
-use std::str::Chars;
-
-use crate::token::Token;
+use crate::token::{Span, Token};
 
 use super::error::TokenizationError;
 
-pub struct Tokenizer<'a> {
-    chars: Peekable<Chars<'a>>,
+/// The math functions that can appear within a math statement, the CSS math functions along with those
+/// supported by Tokens Studio.
+const MATH_FUNCTIONS: [&str; 11] = [
+    "calc", "min", "max", "clamp", "round", "roundto", "floor", "ceil", "sqrt", "pow", "abs",
+];
+
+/// The rounding strategies that can be passed as the first argument of `round()`
+const ROUNDING_STRATEGIES: [&str; 4] = ["nearest", "up", "down", "to-zero"];
+
+/// Converts a math statement into tokens, following the number, dimension and ident-token rules of
+/// CSS Syntax Level 3 (https://www.w3.org/TR/css-syntax-3/#tokenization)
+pub struct Tokenizer {
+    chars: Vec<char>,
+    position: usize,
+    /// Whether the last token produced was an operand (a number, unit, variable or closing parenthesis). A "-"
+    /// following an operand is always a subtraction, otherwise it is the sign of a negative number. This lets us
+    /// tokenize statements like `10px-2px` or `(4 -2)` without relying on whitespace.
+    after_operand: bool,
+}
+
+/// Whether the character can appear within an identifier, i.e. a unit or custom property name.
+fn is_name_code_point(c: char) -> bool {
+    is_name_start_code_point(c) || c.is_ascii_digit() || c == '-'
+}
+
+/// Whether the character can appear at the start of an identifier.
+fn is_name_start_code_point(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
 }
 
-impl<'a> Tokenizer<'a> {
-    pub fn new(input: &'a str) -> Self {
+impl Tokenizer {
+    pub fn new(input: &str) -> Self {
         Tokenizer {
-            chars: input.chars().peekable(),
+            chars: input.chars().collect(),
+            position: 0,
+            after_operand: false,
         }
     }
 
-    fn process_number(&mut self, init: Option<String>) -> Result<Token, TokenizationError> {
-        let mut num = String::from(init.unwrap_or(String::from("")));
+    /// Looks ahead `n` characters without consuming anything.
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.get(self.position + n).copied()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.peek_nth(0)
+    }
 
-        while let Some('0'..='9') | Some('.') = self.chars.peek() {
-            num.push(self.chars.next().unwrap());
+    /// Consumes the next character, keeping track of our position in the input so that tokens
+    /// (and errors) can be mapped back to where they were written.
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.position += 1;
         }
+        c
+    }
 
-        // Push our number token
-        Ok(Token::Number(num))
+    /// Checks if the next characters would start a number (i.e. `5`, `.5`, `-5`, `+.5`)
+    fn starts_number(&self) -> bool {
+        matches!(
+            (self.peek_nth(0), self.peek_nth(1), self.peek_nth(2)),
+            (Some('0'..='9'), _, _)
+                | (Some('.'), Some('0'..='9'), _)
+                | (Some('+' | '-'), Some('0'..='9'), _)
+                | (Some('+' | '-'), Some('.'), Some('0'..='9'))
+        )
     }
 
-    fn process_operator(&mut self) -> Result<Token, TokenizationError> {
-        let mut op = String::new();
+    /// Checks if the next characters are a valid escape, a backslash followed by anything other than a newline.
+    fn starts_escape(&self) -> bool {
+        matches!((self.peek_nth(0), self.peek_nth(1)), (Some('\\'), Some(c)) if c != '\n')
+    }
+
+    fn consume_digits(&mut self, into: &mut String) {
+        while let Some(c @ '0'..='9') = self.peek() {
+            into.push(c);
+            self.advance();
+        }
+    }
+
+    /// Consumes an escape sequence, either up to 6 hex digits (followed by an optional whitespace character) or
+    /// any single character. Escapes are kept as written so they're still valid when output as CSS.
+    fn consume_escape(&mut self, into: &mut String) {
+        into.push(self.advance().unwrap());
 
-        op.push(self.chars.next().unwrap());
+        if matches!(self.peek(), Some(c) if c.is_ascii_hexdigit()) {
+            let mut count = 0;
+            while let Some(c) = self.peek().filter(|c| c.is_ascii_hexdigit() && count < 6) {
+                into.push(c);
+                self.advance();
+                count += 1;
+            }
 
-        if op == String::from("-") && matches!(self.chars.peek(), Some('0'..='9')) {
-            return self.process_number(Some(op.clone()));
+            if let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+                into.push(c);
+                self.advance();
+            }
+        } else {
+            into.push(self.advance().unwrap());
         }
+    }
 
-        Ok(Token::Operator(op))
+    /// Consumes the characters of an identifier. Hyphens are only allowed when `hyphens` is true, none of the
+    /// units in CSS contain a hyphen, and excluding them lets `10px-2px` be read as a subtraction.
+    fn consume_ident(&mut self, hyphens: bool) -> String {
+        let mut ident = String::new();
+
+        loop {
+            match self.peek() {
+                Some('-') if !hyphens => break,
+                Some(c) if is_name_code_point(c) => {
+                    ident.push(c);
+                    self.advance();
+                }
+                Some('\\') if self.starts_escape() => self.consume_escape(&mut ident),
+                _ => break,
+            }
+        }
+
+        ident
     }
 
-    fn process_unit(&mut self) -> Result<Token, TokenizationError> {
-        let mut unit = String::new();
+    fn process_number(&mut self) -> Result<Token, TokenizationError> {
+        let mut num = String::new();
+
+        if let Some(c @ ('+' | '-')) = self.peek() {
+            num.push(c);
+            self.advance();
+        }
 
-        // This first while loop allows us to capture every character that could
-        // potentially be a unit (px|vh|vw|%|rem|...) but will also match "var".
-        while let Some('%') | Some('a'..='z') | Some('A'..='Z') = self.chars.peek() {
-            unit.push(self.chars.next().unwrap());
+        self.consume_digits(&mut num);
+
+        // Decimal part, only if the "." is followed by a digit.
+        if let (Some('.'), Some('0'..='9')) = (self.peek(), self.peek_nth(1)) {
+            num.push(self.advance().unwrap());
+            self.consume_digits(&mut num);
+        }
+
+        // Scientific notation (i.e. 1e3 or 2E-2) which must not be confused with a unit like "em".
+        let exponent = matches!(
+            (self.peek(), self.peek_nth(1), self.peek_nth(2)),
+            (Some('e' | 'E'), Some('0'..='9'), _) | (Some('e' | 'E'), Some('+' | '-'), Some('0'..='9'))
+        );
+
+        if exponent {
+            num.push(self.advance().unwrap());
+            if let Some(c @ ('+' | '-')) = self.peek() {
+                num.push(c);
+                self.advance();
+            }
+            self.consume_digits(&mut num);
         }
 
+        // Push our number token
+        Ok(Token::Number(num))
+    }
+
+    fn process_operator(&mut self) -> Result<Token, TokenizationError> {
+        // A "+" or "-" that doesn't follow an operand is the sign of a number.
+        if !self.after_operand && self.starts_number() {
+            return self.process_number();
+        }
+
+        let op = self.advance().unwrap().to_string();
+
+        Ok(Token::Operator(op))
+    }
+
+    fn process_unit(&mut self, start: usize) -> Result<Token, TokenizationError> {
+        // This allows us to capture every identifier that could potentially be a
+        // unit (px|vh|vw|rem|...) but will also match "var" and function names.
+        // Units always follow a number, anything else is a keyword (like `to-zero`) which may contain hyphens.
+        let unit = self.consume_ident(!self.after_operand);
+
         if unit.starts_with("var") {
-            return self.process_variable(unit);
+            self.process_variable(unit, start)
+        } else if let Some('(') = self.peek() {
+            self.process_function(unit, start)
+        } else if !self.after_operand && ROUNDING_STRATEGIES.contains(&unit.to_lowercase().as_str()) {
+            Ok(Token::Keyword(unit.to_lowercase()))
         } else {
             Ok(Token::Unit(unit))
         }
     }
 
-    fn process_variable(&mut self, input: String) -> Result<Token, TokenizationError> {
-		let mut variable = String::from(input);
+    fn process_function(&mut self, name: String, start: usize) -> Result<Token, TokenizationError> {
+        // Function names in CSS are case-insensitive.
+        let name = name.to_lowercase();
 
-		// Check that the next char is (
-		if let Some('(') = self.chars.peek() {
-			variable.push(self.chars.next().unwrap());
+        if !MATH_FUNCTIONS.contains(&name.as_str()) {
+            return Err(TokenizationError::UnrecognizedFunction(name, Span::new(start, self.position)));
+        }
 
-			// Checks for "--"
-			while let Some('-') = self.chars.peek() {
-				variable.push(self.chars.next().unwrap());
+        // Consume the opening parenthesis, it belongs to the function token.
+        self.advance();
 
-				// Next value should be a-z or "0-9" or the closing parenthesis
-				while let Some('a'..='z') | Some('A'..='Z') | Some('0'..='9') | Some(')') = self.chars.peek() {
-					variable.push(self.chars.next().unwrap());
-				}
-			}
-		}
-		if variable.starts_with("var(--") && variable.ends_with(")") {
-			Ok(Token::Variable(variable))
-		} else {
-			Err(TokenizationError::InvalidVariable(variable))
-		}
-	}
+        Ok(Token::Function(name))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.advance();
+        }
+    }
+
+    /// Consumes a `var()` statement, which may include a fallback value (i.e. `var(--space-sm, 4px)`). The fallback
+    /// can be any CSS value, so it's kept as written rather than being tokenized as part of the math statement.
+    fn process_variable(&mut self, input: String, start: usize) -> Result<Token, TokenizationError> {
+        let invalid = |tokenizer: &mut Self| {
+            // Consume the rest of the var() so the error covers the whole statement.
+            while !matches!(tokenizer.peek(), None | Some(')')) {
+                tokenizer.advance();
+            }
+            tokenizer.advance();
+
+            let variable: String = tokenizer.chars[start..tokenizer.position].iter().collect();
+            Err(TokenizationError::InvalidVariable(variable, Span::new(start, tokenizer.position)))
+        };
+
+        if input != "var" || self.peek() != Some('(') {
+            return invalid(self);
+        }
+        self.advance();
+        self.skip_whitespace();
+
+        // Custom property names must start with "--"
+        if (self.peek(), self.peek_nth(1)) != (Some('-'), Some('-')) {
+            return invalid(self);
+        }
+        self.advance();
+        self.advance();
+
+        let name = self.consume_ident(true);
+        self.skip_whitespace();
+
+        match self.advance() {
+            Some(')') => Ok(Token::Variable(format!("var(--{})", name))),
+            Some(',') => {
+                let mut fallback = String::new();
+                let mut depth = 0;
+
+                // Only consume the closing parenthesis of the var() itself, any that follow belong to the surrounding expression.
+                loop {
+                    match self.advance() {
+                        Some(')') if depth == 0 => break,
+                        Some(c) => {
+                            match c {
+                                '(' => depth += 1,
+                                ')' => depth -= 1,
+                                _ => {}
+                            }
+                            fallback.push(c);
+                        }
+                        None => return invalid(self),
+                    }
+                }
+
+                Ok(Token::Variable(format!("var(--{}, {})", name, fallback.trim())))
+            }
+            _ => invalid(self),
+        }
+    }
 }
 
-impl<'a> Iterator for Tokenizer<'a> {
-    type Item = Result<Token, TokenizationError>;
+impl Iterator for Tokenizer {
+    type Item = Result<(Token, Span), TokenizationError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         // Implement the logic for tokenizing the input string
         loop {
-            if let Some(&c) = self.chars.peek() {
+            let start = self.position;
+
+            let token = if let Some(c) = self.peek() {
                 match c {
-                    '0'..='9' => return Some(self.process_number(None)),
-                    '%' | 'a'..='z' | 'A'..='Z' => return Some(self.process_unit()),
-                    '+' | '-' | '*' | '/' => return Some(self.process_operator()),
-                    ' ' => {
-                        self.chars.next();
+                    '0'..='9' | '.' if self.starts_number() => self.process_number(),
+                    '%' => {
+                        self.advance();
+                        Ok(Token::Unit(String::from("%")))
+                    }
+                    '\\' if self.starts_escape() => self.process_unit(start),
+                    c if is_name_start_code_point(c) => self.process_unit(start),
+                    '+' | '-' | '*' | '/' | '^' => self.process_operator(),
+                    c if c.is_whitespace() => {
+                        self.advance();
                         continue; // skip whitespace and continue iterating
                     }
+                    ',' => {
+                        self.advance();
+                        Ok(Token::Comma)
+                    }
                     '(' => {
-                        self.chars.next();
-                        return Some(Ok(Token::LeftParen));
+                        self.advance();
+                        Ok(Token::LeftParen)
                     }
                     ')' => {
-                        self.chars.next();
-                        return Some(Ok(Token::RightParen));
+                        self.advance();
+                        Ok(Token::RightParen)
                     }
-                    _ => return Some(Err(TokenizationError::UnrecognizedCharacter(c))),
+                    _ => Err(TokenizationError::UnrecognizedCharacter(c, Span::new(start, start + 1))),
                 }
             } else {
                 return None;
+            };
+
+            if let Ok(t) = &token {
+                self.after_operand = matches!(
+                    t,
+                    Token::Number(_) | Token::Unit(_) | Token::Variable(_) | Token::RightParen
+                );
             }
+
+            return Some(token.map(|t| (t, Span::new(start, self.position))));
         }
     }
 }
@@ -126,9 +333,15 @@ mod tests {
     #[test_case("-60.0001")]
     #[test_case("-1")]
     #[test_case("10.5")]
+    #[test_case(".5" ; "leading decimal point")]
+    #[test_case("-.5" ; "negative leading decimal point")]
+    #[test_case("+4" ; "explicit positive sign")]
+    #[test_case("1e3" ; "scientific notation")]
+    #[test_case("2E-2" ; "scientific notation with negative exponent")]
+    #[test_case("1.5e+2" ; "scientific notation with positive exponent")]
     fn handles_number(input: &str) {
         let mut tokenizer = Tokenizer::new(input);
-        assert_matches!(tokenizer.next().unwrap().unwrap(), Token::Number(_));
+        assert_matches!(tokenizer.next().unwrap().unwrap().0, Token::Number(_));
     }
 
     #[test_case("px")]
@@ -142,27 +355,33 @@ mod tests {
     fn handles_unit(input: &str) {
         let mut tokenizer = Tokenizer::new(input);
 		
-        assert_matches!(tokenizer.next().unwrap().unwrap(), Token::Unit(_));
+        assert_matches!(tokenizer.next().unwrap().unwrap().0, Token::Unit(_));
     }
     
 	#[test_case("var(--color)")]
 	#[test_case("var(--typescale-1)")]
 	#[test_case("var(--ref-purple-100)")]
 	#[test_case("var(--gutter)")]
+	#[test_case("var(--space_sm)")]
+	#[test_case("var(--größe)")]
+	#[test_case("var(--space\\.5)")]
+	#[test_case("var(--x, 4px)")]
+	#[test_case("var( --x , var(--y, 2px) )")]
     fn handles_variable(input: &str) {
         let mut tokenizer = Tokenizer::new(input);
 		
-        assert_matches!(tokenizer.next().unwrap().unwrap(), Token::Variable(_));
+        assert_matches!(tokenizer.next().unwrap().unwrap().0, Token::Variable(_));
     }
 
 	#[test_case("varcolor)" ; "missing hyphens in variable name")]
 	#[test_case("var(-color)"  ; "incorrect hyphenation")]
 	#[test_case("var(--color"  ; "missing closing paren")]
+	#[test_case("var(--color, 4px"  ; "missing closing paren after fallback")]
 	fn handles_invalid_variable(input: &str) {
 		let mut tokenizer = Tokenizer::new(input);
 		assert_matches!(
 			tokenizer.next().unwrap(),
-			Err(TokenizationError::InvalidVariable(_))
+			Err(TokenizationError::InvalidVariable(_, _))
 		);
 	}
 
@@ -170,35 +389,111 @@ mod tests {
     #[test_case("-" ; "subtract")]
     #[test_case("/" ; "divide")]
     #[test_case("*" ; "multiply")]
+    #[test_case("^" ; "power")]
     fn handles_operator(input : &str) {
         let mut tokenizer = Tokenizer::new(input);
-        assert_matches!(tokenizer.next().unwrap().unwrap(), Token::Operator(_));
+        assert_matches!(tokenizer.next().unwrap().unwrap().0, Token::Operator(_));
+    }
+
+    #[test_case("calc(" ; "calc")]
+    #[test_case("min(" ; "min")]
+    #[test_case("max(" ; "max")]
+    #[test_case("clamp(" ; "clamp")]
+    #[test_case("CLAMP(" ; "uppercase")]
+    #[test_case("roundTo(" ; "round to")]
+    #[test_case("sqrt(" ; "sqrt")]
+    fn handles_function(input: &str) {
+        let mut tokenizer = Tokenizer::new(input);
+        assert_matches!(tokenizer.next().unwrap().unwrap().0, Token::Function(_));
+    }
+
+    #[test_case("round(up, 2.5, 1)", vec![Token::Function(String::from("round")), Token::Keyword(String::from("up")), Token::Comma, Token::Number(String::from("2.5")), Token::Comma, Token::Number(String::from("1")), Token::RightParen] ; "rounding strategy")]
+    #[test_case("round(TO-ZERO, 1px)", vec![Token::Function(String::from("round")), Token::Keyword(String::from("to-zero")), Token::Comma, Token::Number(String::from("1")), Token::Unit(String::from("px")), Token::RightParen] ; "hyphenated rounding strategy")]
+    fn handles_keyword(input: &str, expected: Vec<Token>) {
+        let tokens: Vec<Token> = Tokenizer::new(input).map(|t| t.unwrap().0).collect();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test_case("rgba(" ; "rgba")]
+    #[test_case("translate(" ; "translate")]
+    fn handles_unrecognized_function(input: &str) {
+        let mut tokenizer = Tokenizer::new(input);
+        assert_matches!(
+            tokenizer.next().unwrap(),
+            Err(TokenizationError::UnrecognizedFunction(_, _))
+        );
+    }
+
+    #[test]
+    fn handles_comma() {
+        let mut tokenizer = Tokenizer::new(",");
+        assert_matches!(tokenizer.next().unwrap().unwrap().0, Token::Comma);
     }
 
     #[test]
     fn handles_left_paren() {
         let input = "(";
         let mut tokenizer = Tokenizer::new(input);
-        assert_matches!(tokenizer.next().unwrap().unwrap(), Token::LeftParen);
+        assert_matches!(tokenizer.next().unwrap().unwrap().0, Token::LeftParen);
     }
 
 	#[test]
     fn handles_right_paren() {
         let input = ")";
         let mut tokenizer = Tokenizer::new(input);
-        assert_matches!(tokenizer.next().unwrap().unwrap(), Token::RightParen);
+        assert_matches!(tokenizer.next().unwrap().unwrap().0, Token::RightParen);
+    }
+
+    #[test_case("10px-2px", vec![Token::Number(String::from("10")), Token::Unit(String::from("px")), Token::Operator(String::from("-")), Token::Number(String::from("2")), Token::Unit(String::from("px"))] ; "subtraction without whitespace")]
+    #[test_case("4 -2", vec![Token::Number(String::from("4")), Token::Operator(String::from("-")), Token::Number(String::from("2"))] ; "subtraction with whitespace before the operator")]
+    #[test_case("4*-2", vec![Token::Number(String::from("4")), Token::Operator(String::from("*")), Token::Number(String::from("-2"))] ; "negative number after an operator")]
+    #[test_case("(-2)-1", vec![Token::LeftParen, Token::Number(String::from("-2")), Token::RightParen, Token::Operator(String::from("-")), Token::Number(String::from("1"))] ; "subtraction after a closing parenthesis")]
+    #[test_case("var(--space-sm)-1px", vec![Token::Variable(String::from("var(--space-sm)")), Token::Operator(String::from("-")), Token::Number(String::from("1")), Token::Unit(String::from("px"))] ; "subtraction after a hyphenated variable")]
+    #[test_case("1\t*\n2", vec![Token::Number(String::from("1")), Token::Operator(String::from("*")), Token::Number(String::from("2"))] ; "tabs and newlines")]
+    fn handles_minus(input: &str, expected: Vec<Token>) {
+        let tokens: Vec<Token> = Tokenizer::new(input).map(|t| t.unwrap().0).collect();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test_case("1em", vec![Token::Number(String::from("1")), Token::Unit(String::from("em"))] ; "unit starting with e")]
+    #[test_case("1e3px", vec![Token::Number(String::from("1e3")), Token::Unit(String::from("px"))] ; "scientific notation with a unit")]
+    #[test_case(".5rem", vec![Token::Number(String::from(".5")), Token::Unit(String::from("rem"))] ; "leading decimal point")]
+    #[test_case("1.5%", vec![Token::Number(String::from("1.5")), Token::Unit(String::from("%"))] ; "decimal percentage")]
+    #[test_case("+4px", vec![Token::Number(String::from("+4")), Token::Unit(String::from("px"))] ; "explicit positive sign")]
+    #[test_case("2+4", vec![Token::Number(String::from("2")), Token::Operator(String::from("+")), Token::Number(String::from("4"))] ; "addition without whitespace")]
+    #[test_case("var( --x , 4px )", vec![Token::Variable(String::from("var(--x, 4px)"))] ; "variable whitespace is normalised")]
+    fn handles_dimension(input: &str, expected: Vec<Token>) {
+        let tokens: Vec<Token> = Tokenizer::new(input).map(|t| t.unwrap().0).collect();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn tracks_spans() {
+        let spans: Vec<Span> = Tokenizer::new("(10px + var(--gap))")
+            .map(|t| t.unwrap().1)
+            .collect();
+
+        assert_eq!(spans, vec![
+            Span::new(0, 1),
+            Span::new(1, 3),
+            Span::new(3, 5),
+            Span::new(6, 7),
+            Span::new(8, 18),
+            Span::new(18, 19),
+        ]);
     }
 
     #[test_case("&" ; "ampersand")]
     #[test_case("@" ; "at")]
-    #[test_case("^" ; "chevron")]
+    #[test_case("#" ; "hash")]
     #[test_case("[" ; "bracket left")]
     #[test_case("]" ; "bracket right")]
+    #[test_case("." ; "lone decimal point")]
     fn handles_unrecognized_character(input: &str) {
         let mut tokenizer = Tokenizer::new(input);
         assert_matches!(
             tokenizer.next().unwrap(),
-            Err(TokenizationError::UnrecognizedCharacter(_))
+            Err(TokenizationError::UnrecognizedCharacter(_, _))
         );
     }
 }
@@ -0,0 +1,81 @@
+use std::fmt;
+
+/// The type of a value in a CSS Math statement, as defined by CSS Values and Units Level 4
+/// (https://www.w3.org/TR/css-values-4/#math)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Type {
+	Number,
+	Length,
+	Percentage,
+	Angle,
+	Time,
+	Frequency,
+	Resolution,
+	Flex,
+}
+
+impl Type {
+	/// Classifies a unit by the type of value it produces, returning `None` for units CSS doesn't recognise.
+	/// Units are case-insensitive (i.e. `10PX` is the same as `10px`)
+	pub fn from_unit(unit: &str) -> Option<Type> {
+		let t = match unit.to_ascii_lowercase().as_str() {
+			"%" => Type::Percentage,
+			// Absolute lengths
+			"px" | "cm" | "mm" | "q" | "in" | "pt" | "pc"
+			// Font-relative lengths
+			| "em" | "rem" | "ex" | "rex" | "cap" | "rcap" | "ch" | "rch" | "ic" | "ric" | "lh" | "rlh"
+			// Viewport-relative lengths
+			| "vw" | "vh" | "vi" | "vb" | "vmin" | "vmax"
+			| "svw" | "svh" | "svi" | "svb" | "svmin" | "svmax"
+			| "lvw" | "lvh" | "lvi" | "lvb" | "lvmin" | "lvmax"
+			| "dvw" | "dvh" | "dvi" | "dvb" | "dvmin" | "dvmax"
+			// Container-relative lengths
+			| "cqw" | "cqh" | "cqi" | "cqb" | "cqmin" | "cqmax" => Type::Length,
+			"deg" | "grad" | "rad" | "turn" => Type::Angle,
+			"s" | "ms" => Type::Time,
+			"hz" | "khz" => Type::Frequency,
+			"dpi" | "dpcm" | "dppx" | "x" => Type::Resolution,
+			"fr" => Type::Flex,
+			_ => return None,
+		};
+
+		Some(t)
+	}
+}
+
+impl fmt::Display for Type {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Type::Number => "a number",
+			Type::Length => "a length",
+			Type::Percentage => "a percentage",
+			Type::Angle => "an angle",
+			Type::Time => "a time",
+			Type::Frequency => "a frequency",
+			Type::Resolution => "a resolution",
+			Type::Flex => "a flex value",
+		};
+
+		write!(f, "{}", name)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use test_case::test_case;
+
+	#[test_case("px", Some(Type::Length))]
+	#[test_case("REM", Some(Type::Length))]
+	#[test_case("cqi", Some(Type::Length))]
+	#[test_case("%", Some(Type::Percentage))]
+	#[test_case("turn", Some(Type::Angle))]
+	#[test_case("ms", Some(Type::Time))]
+	#[test_case("kHz", Some(Type::Frequency))]
+	#[test_case("dppx", Some(Type::Resolution))]
+	#[test_case("fr", Some(Type::Flex))]
+	#[test_case("pixels", None)]
+	fn from_unit(unit: &str, expected: Option<Type>) {
+		assert_eq!(Type::from_unit(unit), expected);
+	}
+}
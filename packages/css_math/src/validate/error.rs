@@ -1,12 +1,72 @@
+use std::fmt;
+
+use crate::token::Span;
+use crate::types::Type;
+
 #[derive(Debug, PartialEq)]
 pub enum ValidationError {
-    DivisionByZero(String),
-    IncompleteExpression(String),
-    InvalidDivisionRHS(String),
-	InvalidVariable(String),
-	InvalidNumber(String),
-    InvalidSyntax(String),
-	NoOperators(String),
-	MismatchedParentheses(String),
-    MultiplicationWithUnits(String)
-}
\ No newline at end of file
+    DivisionByZero(Span),
+    IncompleteExpression(Span),
+    InvalidDivisionRHS(Type, Span),
+	InvalidVariable(Span),
+	InvalidNumber(Span),
+    InvalidSyntax(Span),
+	NoOperators(Span),
+	MismatchedParentheses(Span),
+	MismatchedArguments(Span),
+	InvalidArgumentCount(Span),
+	IncompatibleTypes(Type, Type, Span),
+	UnknownUnit(Span),
+    MultiplicationWithUnits(Span),
+	ExpectedNumber(Type, Span),
+	/// A Tokens Studio rounding function (i.e. `floor(var(--a))`) whose unit can't be known, so there's no step to
+	/// print it as CSS round() with
+	UnknownRoundingUnit(Span),
+}
+
+impl ValidationError {
+	/// The location of the token that caused validation to fail.
+	pub fn span(&self) -> Span {
+		match self {
+			ValidationError::DivisionByZero(span)
+			| ValidationError::IncompleteExpression(span)
+			| ValidationError::InvalidDivisionRHS(_, span)
+			| ValidationError::InvalidVariable(span)
+			| ValidationError::InvalidNumber(span)
+			| ValidationError::InvalidSyntax(span)
+			| ValidationError::NoOperators(span)
+			| ValidationError::MismatchedParentheses(span)
+			| ValidationError::MismatchedArguments(span)
+			| ValidationError::InvalidArgumentCount(span)
+			| ValidationError::IncompatibleTypes(_, _, span)
+			| ValidationError::UnknownUnit(span)
+			| ValidationError::MultiplicationWithUnits(span)
+			| ValidationError::ExpectedNumber(_, span)
+			| ValidationError::UnknownRoundingUnit(span) => *span,
+		}
+	}
+}
+
+impl fmt::Display for ValidationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ValidationError::DivisionByZero(_) => write!(f, "division by zero"),
+			ValidationError::IncompleteExpression(_) => write!(f, "incomplete expression"),
+			ValidationError::InvalidDivisionRHS(t, _) => write!(f, "division by {}", t),
+			ValidationError::InvalidVariable(_) => write!(f, "invalid variable"),
+			ValidationError::InvalidNumber(_) => write!(f, "invalid number"),
+			ValidationError::InvalidSyntax(_) => write!(f, "unexpected token"),
+			ValidationError::NoOperators(_) => write!(f, "no math operators"),
+			ValidationError::MismatchedParentheses(_) => write!(f, "mismatched parentheses"),
+			ValidationError::MismatchedArguments(_) => write!(f, "function arguments of different types"),
+			ValidationError::InvalidArgumentCount(_) => write!(f, "wrong number of function arguments"),
+			ValidationError::IncompatibleTypes(a, b, _) => write!(f, "cannot combine {} with {}", a, b),
+			ValidationError::UnknownUnit(_) => write!(f, "unknown unit"),
+			ValidationError::MultiplicationWithUnits(_) => write!(f, "multiplication of two values with units"),
+			ValidationError::ExpectedNumber(t, _) => write!(f, "expected a number, found {}", t),
+			ValidationError::UnknownRoundingUnit(_) => write!(f, "cannot round a value of unknown unit"),
+		}
+	}
+}
+
+impl std::error::Error for ValidationError {}
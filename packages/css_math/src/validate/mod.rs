@@ -1,12 +1,18 @@
 mod error;
+pub use error::ValidationError;
 mod validator;
 use validator::validator;
+mod type_checker;
+use type_checker::type_checker;
 
-use crate::token::Token;
+use crate::ast::Expression;
+use crate::token::{Span, Token};
 
-pub(crate) fn validate(tokens: &[Token]) -> bool {
-    match validator(tokens) {
-        Ok(_) => true,
-        Err(_) => false,
-    }
+pub(crate) fn validate(tokens: &[(Token, Span)]) -> Result<(), ValidationError> {
+    validator(tokens)
+}
+
+/// Checks the types of every operation in a parsed expression are compatible, see `type_checker`
+pub(crate) fn check_types(expression: &Expression) -> Result<(), ValidationError> {
+    type_checker(expression).map(|_| ())
 }
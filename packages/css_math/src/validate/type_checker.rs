@@ -0,0 +1,197 @@
+use super::error::ValidationError;
+use crate::ast::{Expression, ExpressionKind, Function, Operator};
+use crate::token::Span;
+use crate::types::Type;
+
+/// Walks the expression tree, computing the type of each sub-expression and rejecting operations on
+/// incompatible types (i.e. `10px + 2s`, or `min(100%, 2)`).
+///
+/// Returns the type the whole expression resolves to, or `None` if it can't be known ahead of time because
+/// it depends on the value of a `var()`
+pub(crate) fn type_checker(expression: &Expression) -> Result<Option<Type>, ValidationError> {
+    match &expression.kind {
+        ExpressionKind::Number(_) => Ok(Some(Type::Number)),
+        ExpressionKind::Dimension(_, unit) => Type::from_unit(unit)
+            .map(Some)
+            .ok_or(ValidationError::UnknownUnit(expression.span)),
+        ExpressionKind::Variable(_) => Ok(None),
+        ExpressionKind::Binary(Operator::Power, lhs, rhs) => {
+            number(lhs)?;
+            number(rhs)?;
+            Ok(Some(Type::Number))
+        }
+        ExpressionKind::Binary(op, lhs, rhs) => {
+            let lhs = type_checker(lhs)?;
+            let rhs = type_checker(rhs)?;
+
+            match op {
+                Operator::Add | Operator::Subtract => add(lhs, rhs, expression.span),
+                Operator::Multiply => multiply(lhs, rhs, expression.span),
+                Operator::Divide => divide(lhs, rhs, expression.span),
+                Operator::Power => unreachable!("exponents are checked above"),
+            }
+        }
+        // Exponents and square roots only make sense for plain numbers, i.e. `pow(1.25, 3)`
+        ExpressionKind::Function(Function::Sqrt | Function::Pow, arguments) => {
+            for argument in arguments {
+                number(argument)?;
+            }
+
+            Ok(Some(Type::Number))
+        }
+        // Rounding keeps the type of the value being rounded, the number of decimal places must be a number.
+        ExpressionKind::Function(Function::RoundTo, arguments) => {
+            if let Some(places) = arguments.get(1) {
+                number(places)?;
+            }
+
+            type_checker(&arguments[0])
+        }
+        ExpressionKind::Function(Function::Round | Function::Floor | Function::Ceil | Function::Abs, arguments) => {
+            type_checker(&arguments[0])
+        }
+        // round(<strategy>, A, B) rounds A to a multiple of B, so they must share a type. B defaults to 1, which can
+        // only be left out when A is a number.
+        ExpressionKind::Function(Function::RoundStep(_), arguments) => match arguments.get(1) {
+            Some(step) => unify(type_checker(&arguments[0])?, type_checker(step)?)
+                .map_err(|_| ValidationError::MismatchedArguments(step.span)),
+            None => {
+                number(&arguments[0])?;
+                Ok(Some(Type::Number))
+            }
+        },
+        ExpressionKind::Function(_, arguments) => {
+            let mut result = None;
+
+            // Every argument of a math function must resolve to the same type, for example `min(100%, 960px)`
+            // is valid, but `min(100%, 2)` is not.
+            for argument in arguments {
+                result = unify(result, type_checker(argument)?)
+                    .map_err(|_| ValidationError::MismatchedArguments(argument.span))?;
+            }
+
+            Ok(result)
+        }
+    }
+}
+
+/// Checks that an expression resolves to a plain number, or could do once its variables are known.
+fn number(expression: &Expression) -> Result<(), ValidationError> {
+    match type_checker(expression)? {
+        Some(Type::Number) | None => Ok(()),
+        Some(t) => Err(ValidationError::ExpectedNumber(t, expression.span)),
+    }
+}
+
+/// Combines the types of two values that are added together, or compared with each other.
+fn unify(a: Option<Type>, b: Option<Type>) -> Result<Option<Type>, (Type, Type)> {
+    match (a, b) {
+        (None, other) | (other, None) => Ok(other),
+        (Some(a), Some(b)) if a == b => Ok(Some(a)),
+        // Percentages resolve against another type depending on where they are used, i.e. `100% - 16px`
+        // is a length. Numbers can never be combined with a percentage.
+        (Some(Type::Percentage), Some(other)) | (Some(other), Some(Type::Percentage)) if other != Type::Number => {
+            Ok(Some(other))
+        }
+        (Some(a), Some(b)) => Err((a, b)),
+    }
+}
+
+fn add(lhs: Option<Type>, rhs: Option<Type>, span: Span) -> Result<Option<Type>, ValidationError> {
+    unify(lhs, rhs).map_err(|(a, b)| ValidationError::IncompatibleTypes(a, b, span))
+}
+
+fn multiply(lhs: Option<Type>, rhs: Option<Type>, span: Span) -> Result<Option<Type>, ValidationError> {
+    // At least one side of a multiplication must be a number, the result takes the type of the other side.
+    match (lhs, rhs) {
+        (Some(Type::Number), other) | (other, Some(Type::Number)) => Ok(other),
+        (None, other) | (other, None) => Ok(other),
+        _ => Err(ValidationError::MultiplicationWithUnits(span)),
+    }
+}
+
+fn divide(lhs: Option<Type>, rhs: Option<Type>, span: Span) -> Result<Option<Type>, ValidationError> {
+    // The right hand side of a division must be a number.
+    match rhs {
+        Some(Type::Number) | None => Ok(lhs),
+        Some(t) => Err(ValidationError::InvalidDivisionRHS(t, span)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+    use crate::tokenize::tokenize;
+    use matches::assert_matches;
+    use test_case::test_case;
+
+    fn check(input: &str) -> Result<Option<Type>, ValidationError> {
+        let tokens = tokenize(input).unwrap();
+        type_checker(&parse(&tokens).unwrap())
+    }
+
+    #[test_case("10px + 2rem", Some(Type::Length) ; "lengths")]
+    #[test_case("100% - 16px", Some(Type::Length) ; "percentage resolves against length")]
+    #[test_case("50% * 2", Some(Type::Percentage) ; "percentage")]
+    #[test_case("45deg + 0.5turn", Some(Type::Angle) ; "angles")]
+    #[test_case("150ms * 2 + 1s", Some(Type::Time) ; "times")]
+    #[test_case("2 * 3 / 4", Some(Type::Number) ; "numbers")]
+    #[test_case("1fr * 2", Some(Type::Flex) ; "flex")]
+    #[test_case("var(--duration) * 2", None ; "variable")]
+    #[test_case("var(--duration) + 100ms", Some(Type::Time) ; "variable added to a time")]
+    #[test_case("16px * 1.25 ^ 3", Some(Type::Length) ; "exponent")]
+    #[test_case("sqrt(var(--ratio))", Some(Type::Number) ; "square root")]
+    #[test_case("roundTo(var(--base) * 1.5, 2)", None ; "round to")]
+    #[test_case("floor(10.5px)", Some(Type::Length) ; "floor")]
+    #[test_case("round(down, 10.5px, 1rem)", Some(Type::Length) ; "round with a step")]
+    #[test_case("round(up, var(--a), 1px)", Some(Type::Length) ; "round a variable with a step")]
+    fn resolves(input: &str, expected: Option<Type>) {
+        assert_eq!(check(input).unwrap(), expected);
+    }
+
+    #[test_case("min(100%, 960px)" ; "dimensions")]
+    #[test_case("max(1, 2 * 3)" ; "numbers")]
+    #[test_case("clamp(1rem, var(--fluid), 3rem)" ; "variable")]
+    #[test_case("clamp(1rem, 2vw + 1rem, 3rem)" ; "expression")]
+    fn valid(input: &str) {
+        assert!(check(input).is_ok());
+    }
+
+    #[test_case("min(100%, 2)" ; "dimension and number")]
+    #[test_case("clamp(1, 2vw, 3rem)" ; "number and dimensions")]
+    #[test_case("calc(max(1px, 2) * 2)" ; "nested function")]
+    #[test_case("max(1s, 10px)" ; "time and length")]
+    #[test_case("round(down, 10.5px, 1s)" ; "round with a step of another type")]
+    fn invalid(input: &str) {
+        assert_matches!(check(input), Err(ValidationError::MismatchedArguments(_)));
+    }
+
+    #[test_case("10px + 2s", Type::Length, Type::Time ; "length and time")]
+    #[test_case("45deg - 4px", Type::Angle, Type::Length ; "angle and length")]
+    #[test_case("10px + 2", Type::Length, Type::Number ; "length and number")]
+    #[test_case("50% + 1", Type::Percentage, Type::Number ; "percentage and number")]
+    fn incompatible_types(input: &str, a: Type, b: Type) {
+        assert_matches!(check(input), Err(ValidationError::IncompatibleTypes(x, y, _)) if x == a && y == b);
+    }
+
+    #[test]
+    fn division_by_dimension() {
+        assert_matches!(check("10px / (2px + 1px)"), Err(ValidationError::InvalidDivisionRHS(Type::Length, _)));
+    }
+
+    #[test_case("16px ^ 2" ; "exponent of a length")]
+    #[test_case("2 ^ 1s" ; "exponent is a time")]
+    #[test_case("pow(2, 4px)" ; "pow of a length")]
+    #[test_case("sqrt(100%)" ; "square root of a percentage")]
+    #[test_case("roundTo(1.5px, 1px)" ; "decimal places with a unit")]
+    #[test_case("round(up, 10.5px)" ; "round a length without a step")]
+    fn expected_number(input: &str) {
+        assert_matches!(check(input), Err(ValidationError::ExpectedNumber(_, _)));
+    }
+
+    #[test]
+    fn unknown_unit() {
+        assert_matches!(check("10pixels + 2px"), Err(ValidationError::UnknownUnit(_)));
+    }
+}
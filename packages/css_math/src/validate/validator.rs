@@ -1,145 +1,215 @@
 use super::error::ValidationError;
-use crate::token::Token;
+use crate::token::{Span, Token};
+use crate::types::Type;
 
-fn tokens_to_string(tokens: &[Token]) -> String {
-    let mut result = String::new();
-    for token in tokens {
+/// Finds the first parenthesis that doesn't have a matching partner, if there is one.
+fn unmatched_parenthesis(t: &[(Token, Span)]) -> Option<Span> {
+    let mut open: Vec<Span> = Vec::new();
+
+    for (token, span) in t {
         match token {
-            Token::Number(num) => result.push_str(num),
-            Token::Unit(unit) => result.push_str(unit),
-            Token::Operator(op) => {
-                // Optionally add spaces around operators for readability
-                result.push(' ');
-                result.push_str(op);
-                result.push(' ');
-            },
-            Token::LeftParen => result.push('('),
-            Token::RightParen => result.push(')'),
-            Token::Variable(var) => result.push_str(var),
+            Token::LeftParen | Token::Function(_) => open.push(*span),
+            Token::RightParen if open.pop().is_none() => return Some(*span),
+            _ => {},
         }
     }
-    result
+
+    open.first().copied()
+}
+
+/// Keeps track of the state of an operation, or nested operation, as we walk through the tokens.
+#[derive(Default)]
+struct Context<'a> {
+    /// The latest operator within this context.
+    operator: Option<&'a str>,
+    /// The latest unit within the current operation.
+    unit: Option<&'a str>,
+    /// The latest token within this context.
+    token: Option<&'a Token>,
+    /// The name of the math function that opened this context, if it wasn't opened by a plain parenthesis.
+    function: Option<&'a str>,
+    /// The number of arguments passed to the function so far.
+    arguments: usize,
+}
+
+/// Checks that a math function has been passed the number of arguments it expects.
+fn valid_argument_count(function: &str, count: usize) -> bool {
+    match function {
+        "calc" | "floor" | "ceil" | "sqrt" | "abs" => count == 1,
+        "roundto" => count == 1 || count == 2,
+        // round(A), round(A, B) or round(<strategy>, A, B?)
+        "round" => (1..=3).contains(&count),
+        "pow" => count == 2,
+        "clamp" => count == 3,
+        _ => count >= 1,
+    }
 }
 
-pub(crate) fn validator(t: &[Token]) -> Result<(), ValidationError> {
-    let parentheses = t.iter().fold(0, |count, current| match current {
-        Token::LeftParen => count + 1,
-        Token::RightParen => count - 1,
-        _ => count,
-    });
+pub(crate) fn validator(t: &[(Token, Span)]) -> Result<(), ValidationError> {
+    // A span covering the whole input, used for errors that can't be attributed to a single token.
+    let input_span = match (t.first(), t.last()) {
+        (Some((_, first)), Some((_, last))) => first.to(*last),
+        _ => Span::new(0, 0),
+    };
 
-    if parentheses != 0 {
-        return Err(ValidationError::MismatchedParentheses(tokens_to_string(t)));
+    if let Some(span) = unmatched_parenthesis(t) {
+        return Err(ValidationError::MismatchedParentheses(span));
     }
 
-    // If there are no operators, then we don't have a math statement.
-    let operators: bool = t.iter().any(|c| matches!(c, Token::Operator(_)));
+    // If there are no operators or math functions, then we don't have a math statement.
+    let operators: bool = t.iter().any(|(c, _)| matches!(c, Token::Operator(_) | Token::Function(_)));
 
     if !operators {
-        return Err(ValidationError::NoOperators(tokens_to_string(t)));
+        return Err(ValidationError::NoOperators(input_span));
     }
 
-    // Initialize context stack to keep track of operations, and nested operations,
-    // Each item on the stack is a tuple of (latest_operator, latest_unit, latest_token)
-    let mut ctx_stack: Vec<(Option<&str>, Option<&str>, Option<&Token>)> = Vec::new();
-    ctx_stack.push((None, None, None));
+    // Initialize context stack to keep track of operations, and nested operations.
+    let mut ctx_stack: Vec<Context> = vec![Context::default()];
+
+    for (token, span) in t {
+        let span = *span;
 
-    for token in t {
         match token {
-            // Left parentheses create a new context in the stack
-            Token::LeftParen => {
+            // Left parentheses and functions create a new context in the stack
+            Token::LeftParen | Token::Function(_) => {
                 let context = ctx_stack.last_mut().unwrap();
 
                 // For Left paren, we push the token to the current context before creating a new one
                 // This ensures that parentheses are treated as a member of the context they are written in
                 // i.e. not a part of the context they delimit.
-                context.2 = Some(token);
+                context.token = Some(token);
+
+                let function = match token {
+                    Token::Function(name) => Some(name.as_str()),
+                    _ => None,
+                };
+
+                ctx_stack.push(Context { function, ..Context::default() })
+            },
+            // Commas separate the arguments of a function, each argument is an expression in its own right.
+            Token::Comma => {
+                let context = ctx_stack.last_mut().unwrap();
+
+                if context.function.is_none() {
+                    return Err(ValidationError::InvalidSyntax(span));
+                }
+
+                if matches!(context.token, None | Some(Token::Operator(_))) {
+                    return Err(ValidationError::IncompleteExpression(span));
+                }
 
-                ctx_stack.push((None, None, None))
+                *context = Context {
+                    function: context.function,
+                    arguments: context.arguments + 1,
+                    ..Context::default()
+                };
             },
             // Right parentheses pops the latest context from the stack
             Token::RightParen => {
-                ctx_stack.pop();
-                let stack_len = ctx_stack.len();
-                if stack_len == 0 {
-                    return Err(ValidationError::MismatchedParentheses(tokens_to_string(t)))
+                let context = ctx_stack.pop().unwrap();
+
+                if let Some(function) = context.function {
+                    if matches!(context.token, None | Some(Token::Operator(_))) {
+                        return Err(ValidationError::IncompleteExpression(span));
+                    }
+
+                    if !valid_argument_count(function, context.arguments + 1) {
+                        return Err(ValidationError::InvalidArgumentCount(span));
+                    }
+                }
+
+                if ctx_stack.is_empty() {
+                    return Err(ValidationError::MismatchedParentheses(span))
                 }
 
                 // For Right paren, we push the token to the context after removing the previous from the stack
                 // This ensures that parentheses are treated as a member of the context they are written in
                 // i.e. not a part of the context they delimit.
                 let context = ctx_stack.last_mut().unwrap();
-                context.2 = Some(token);
+                context.token = Some(token);
             },
             Token::Operator(op) => {
                 let context = ctx_stack.last_mut().unwrap();
 
-                context.0 = Some(op);
+                context.operator = Some(op);
                 if op != "/" && op != "*" {
-                    context.1 = None;
+                    context.unit = None;
                 }
 
-                if matches!(context.2, None) {
-                    return Err(ValidationError::InvalidSyntax(tokens_to_string(t)));
+                if context.token.is_none() {
+                    return Err(ValidationError::InvalidSyntax(span));
                 }
 
-                context.2 = Some(token);
+                context.token = Some(token);
             },
             Token::Unit(unit) => {
                 let context = ctx_stack.last_mut().unwrap();
 
                 // If we hit a unit, and we didn't previously have a number then error
-                if !matches!(context.2, Some(Token::Number(_))) {
-                    return Err(ValidationError::InvalidSyntax(tokens_to_string(t)))
+                if !matches!(context.token, Some(Token::Number(_))) {
+                    return Err(ValidationError::InvalidSyntax(span))
                 }
 
+                let unit_type = Type::from_unit(unit).ok_or(ValidationError::UnknownUnit(span))?;
+
                 // If in a division operation, there should be no units on the RHS
-                if matches!(context.0, Some("/")) {
-                    return Err(ValidationError::InvalidDivisionRHS(tokens_to_string(t)));
+                if matches!(context.operator, Some("/")) {
+                    return Err(ValidationError::InvalidDivisionRHS(unit_type, span));
                 }
 
                 // If in a multiplication operation and there has already been a unit
                 // there should be no more units in the operation
-                if matches!(context.0, Some("*")) && context.1.is_some() {
-                    return Err(ValidationError::MultiplicationWithUnits(tokens_to_string(t)));
+                if matches!(context.operator, Some("*")) && context.unit.is_some() {
+                    return Err(ValidationError::MultiplicationWithUnits(span));
                 }
 
-                context.1 = Some(unit);
-                context.2 = Some(token);
+                context.unit = Some(unit);
+                context.token = Some(token);
             },
             Token::Number(num) => {
                 let context = ctx_stack.last_mut().unwrap();
 
                 // Number should only ever follow None or an operator.
-                if !matches!(context.2, None | Some(Token::Operator(_))) {
-                    return Err(ValidationError::InvalidSyntax(tokens_to_string(t)))
+                if !matches!(context.token, None | Some(Token::Operator(_))) {
+                    return Err(ValidationError::InvalidSyntax(span))
                 }
 
-                if matches!(context.0, Some("/")) && num == "0" {
-                    return Err(ValidationError::DivisionByZero(tokens_to_string(t)))
+                if matches!(context.operator, Some("/")) && num == "0" {
+                    return Err(ValidationError::DivisionByZero(span))
                 }
 
                 // Floats should always include the trailing digits (Number should never end in ".")
-                if num.ends_with(".") {
-                    return Err(ValidationError::InvalidNumber(tokens_to_string(t)));
+                if num.ends_with('.') {
+                    return Err(ValidationError::InvalidNumber(span));
                 }
 
-                context.2 = Some(token);
+                context.token = Some(token);
+            },
+            // Keywords can only be the first argument of a function, i.e. `round(down, 10.5px, 1px)`
+            Token::Keyword(_) => {
+                let context = ctx_stack.last_mut().unwrap();
+
+                if context.function != Some("round") || context.arguments > 0 || context.token.is_some() {
+                    return Err(ValidationError::InvalidSyntax(span))
+                }
+
+                context.token = Some(token);
             },
             Token::Variable(value) => {
                 let context = ctx_stack.last_mut().unwrap();
 
-                if !value.starts_with("var(--") || !value.ends_with(")") {
-                    return Err(ValidationError::InvalidVariable(tokens_to_string(t)))
+                if !value.starts_with("var(--") || !value.ends_with(')') {
+                    return Err(ValidationError::InvalidVariable(span))
                 }
 
-                context.2 = Some(token);
+                context.token = Some(token);
             }
         }
     }
 
-    if ctx_stack.len() > 1 || matches!(ctx_stack[0].2, Some(Token::Operator(_))) {
-        return Err(ValidationError::IncompleteExpression(tokens_to_string(t)))
+    if ctx_stack.len() > 1 || matches!(ctx_stack[0].token, Some(Token::Operator(_))) {
+        let last = t.last().map(|(_, span)| *span).unwrap_or(input_span);
+        return Err(ValidationError::IncompleteExpression(last))
     }
 
     Ok(())
@@ -148,7 +218,25 @@ pub(crate) fn validator(t: &[Token]) -> Result<(), ValidationError> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use matches::assert_matches;
     use test_case::test_case;
+
+    /// Attaches a span to each token, as if the tokens had been written out without any whitespace.
+    fn spanned(tokens: &[Token]) -> Vec<(Token, Span)> {
+        let mut position = 0;
+
+        tokens.iter().map(|token| {
+            let len = match token {
+                Token::Number(s) | Token::Unit(s) | Token::Variable(s) | Token::Operator(s) | Token::Keyword(s) => s.chars().count(),
+                Token::Function(name) => name.chars().count() + 1,
+                Token::Comma | Token::LeftParen | Token::RightParen => 1,
+            };
+            let span = Span::new(position, position + len);
+            position += len;
+
+            (token.clone(), span)
+        }).collect()
+    }
     
     #[test_case(&[Token::Number("100".to_string()), Token::Unit("%".to_string()), Token::Operator("-".to_string()), Token::Number("50".to_string()), Token::Unit("px".to_string())]; "subtract px from percent")]
     #[test_case(&[Token::Number("100".to_string()), Token::Unit("%".to_string()), Token::Operator("*".to_string()), Token::Number("2".to_string())]; "multiply percent by number")]
@@ -201,8 +289,69 @@ mod test {
         Token::Number("50".to_string()), 
         Token::RightParen
     ]; "nested division and addition")]
+    #[test_case(&[
+        Token::Function("min".to_string()),
+        Token::Number("100".to_string()),
+        Token::Unit("%".to_string()),
+        Token::Comma,
+        Token::Number("960".to_string()),
+        Token::Unit("px".to_string()),
+        Token::RightParen
+    ]; "min with two arguments")]
+    #[test_case(&[
+        Token::Function("clamp".to_string()),
+        Token::Number("1".to_string()),
+        Token::Unit("rem".to_string()),
+        Token::Comma,
+        Token::Number("2".to_string()),
+        Token::Unit("vw".to_string()),
+        Token::Operator("+".to_string()),
+        Token::Number("1".to_string()),
+        Token::Unit("rem".to_string()),
+        Token::Comma,
+        Token::Number("3".to_string()),
+        Token::Unit("rem".to_string()),
+        Token::RightParen
+    ]; "clamp with an expression argument")]
+    #[test_case(&[
+        Token::Function("calc".to_string()),
+        Token::Number("100".to_string()),
+        Token::Unit("%".to_string()),
+        Token::Operator("-".to_string()),
+        Token::Number("16".to_string()),
+        Token::Unit("px".to_string()),
+        Token::RightParen,
+        Token::Operator("*".to_string()),
+        Token::Number("2".to_string())
+    ]; "nested calc multiplied by number")]
+    #[test_case(&[
+        Token::Number("16".to_string()),
+        Token::Unit("px".to_string()),
+        Token::Operator("*".to_string()),
+        Token::Number("1.25".to_string()),
+        Token::Operator("^".to_string()),
+        Token::Number("3".to_string())
+    ]; "exponent")]
+    #[test_case(&[
+        Token::Function("roundto".to_string()),
+        Token::Number("1.2345".to_string()),
+        Token::Comma,
+        Token::Number("2".to_string()),
+        Token::RightParen
+    ]; "round to with two arguments")]
+    #[test_case(&[
+        Token::Function("round".to_string()),
+        Token::Keyword("down".to_string()),
+        Token::Comma,
+        Token::Number("10.5".to_string()),
+        Token::Unit("px".to_string()),
+        Token::Comma,
+        Token::Number("1".to_string()),
+        Token::Unit("px".to_string()),
+        Token::RightParen
+    ]; "round with a rounding strategy")]
     fn valid(input: &[Token]) {
-        let result = validator(input);
+        let result = validator(&spanned(input));
         assert!(result.is_ok())
     }
 
@@ -323,8 +472,82 @@ mod test {
         Token::Number("2".to_string()), 
         Token::Unit("px".to_string())
     ]; "unit mismatch in division after nested expression")]
+    #[test_case(&[
+        Token::Function("clamp".to_string()),
+        Token::Number("1".to_string()),
+        Token::Unit("rem".to_string()),
+        Token::Comma,
+        Token::Number("3".to_string()),
+        Token::Unit("rem".to_string()),
+        Token::RightParen
+    ]; "clamp with two arguments")]
+    #[test_case(&[
+        Token::Function("calc".to_string()),
+        Token::Number("1".to_string()),
+        Token::Comma,
+        Token::Number("2".to_string()),
+        Token::RightParen
+    ]; "calc with two arguments")]
+    #[test_case(&[
+        Token::Function("pow".to_string()),
+        Token::Number("2".to_string()),
+        Token::RightParen
+    ]; "pow with one argument")]
+    #[test_case(&[
+        Token::Function("min".to_string()),
+        Token::Number("1".to_string()),
+        Token::Unit("rem".to_string()),
+        Token::Comma,
+        Token::RightParen
+    ]; "empty function argument")]
+    #[test_case(&[
+        Token::Number("1".to_string()),
+        Token::Unit("rem".to_string()),
+        Token::Comma,
+        Token::Number("2".to_string()),
+        Token::Unit("rem".to_string())
+    ]; "comma outside of a function")]
+    #[test_case(&[
+        Token::Function("min".to_string()),
+        Token::Keyword("up".to_string()),
+        Token::Comma,
+        Token::Number("1".to_string()),
+        Token::RightParen
+    ]; "keyword outside of round")]
+    #[test_case(&[
+        Token::Function("round".to_string()),
+        Token::Number("1".to_string()),
+        Token::Comma,
+        Token::Keyword("up".to_string()),
+        Token::RightParen
+    ]; "keyword after the first argument")]
     fn invalid(input: &[Token]) {
-        let result = validator(input);
+        let result = validator(&spanned(input));
         assert!(result.is_err())
     }
+
+    #[test]
+    fn reports_position_of_invalid_token() {
+        let input = spanned(&[
+            Token::Number("100".to_string()),
+            Token::Unit("px".to_string()),
+            Token::Operator("/".to_string()),
+            Token::Number("2".to_string()),
+            Token::Unit("px".to_string()),
+        ]);
+
+        assert_matches!(validator(&input), Err(ValidationError::InvalidDivisionRHS(Type::Length, span)) if span == Span::new(7, 9));
+    }
+
+    #[test]
+    fn reports_position_of_unmatched_parenthesis() {
+        let input = spanned(&[
+            Token::Number("1".to_string()),
+            Token::Operator("+".to_string()),
+            Token::LeftParen,
+            Token::Number("2".to_string()),
+        ]);
+
+        assert_matches!(validator(&input), Err(ValidationError::MismatchedParentheses(span)) if span == Span::new(2, 3));
+    }
 }
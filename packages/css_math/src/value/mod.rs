@@ -0,0 +1,105 @@
+use std::fmt;
+
+use crate::types::Type;
+
+/// The maximum number of decimal places kept when printing a computed value.
+const PRECISION: i32 = 4;
+
+/// The result of evaluating a CSS Math statement, a number with an optional unit (i.e. `8px`, `1.5rem`, `50%` or `2`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Value {
+	pub number: f64,
+	pub unit: Option<String>,
+}
+
+impl Value {
+	pub fn new(number: f64, unit: Option<String>) -> Self {
+		Value { number, unit }
+	}
+
+	/// Converts the value to another unit, this is only possible if the units are the same, or are both absolute units
+	/// of the same type with a fixed ratio between them (i.e. `1s` is `1000ms`, but `1rem` can't be known in `px`)
+	pub fn convert(&self, unit: &Option<String>) -> Option<Value> {
+		if &self.unit == unit {
+			return Some(self.clone());
+		}
+
+		let (from_type, from) = Type::from_unit(self.unit.as_deref()?).zip(ratio(self.unit.as_deref()?))?;
+		let (to_type, to) = Type::from_unit(unit.as_deref()?).zip(ratio(unit.as_deref()?))?;
+
+		if from_type != to_type {
+			return None;
+		}
+
+		Some(Value::new(self.number * from / to, unit.clone()))
+	}
+
+	/// The value rounded to the number of decimal places it's printed with, see `PRECISION`
+	pub(crate) fn rounded(&self) -> Value {
+		let factor = 10f64.powi(PRECISION);
+		let mut number = (self.number * factor).round() / factor;
+
+		// Avoid printing "-0" when a negative result rounds away.
+		if number == 0.0 {
+			number = 0.0;
+		}
+
+		Value::new(number, self.unit.clone())
+	}
+}
+
+/// The size of an absolute unit relative to the canonical unit of its type (px, deg, ms, hz and dppx), as defined by
+/// CSS Values and Units Level 4
+fn ratio(unit: &str) -> Option<f64> {
+	let ratio = match unit.to_ascii_lowercase().as_str() {
+		"px" | "deg" | "ms" | "hz" | "dppx" | "x" => 1.0,
+		"in" => 96.0,
+		"cm" => 96.0 / 2.54,
+		"mm" => 96.0 / 25.4,
+		"q" => 96.0 / 101.6,
+		"pt" => 96.0 / 72.0,
+		"pc" => 16.0,
+		"grad" => 0.9,
+		"rad" => 180.0 / std::f64::consts::PI,
+		"turn" => 360.0,
+		"s" => 1000.0,
+		"khz" => 1000.0,
+		"dpi" => 1.0 / 96.0,
+		"dpcm" => 2.54 / 96.0,
+		_ => return None,
+	};
+
+	Some(ratio)
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}{}", self.rounded().number, self.unit.as_deref().unwrap_or(""))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use test_case::test_case;
+
+	#[test_case(8.0, Some("px"), "8px")]
+	#[test_case(1.5, Some("rem"), "1.5rem")]
+	#[test_case(2.0, None, "2")]
+	#[test_case(10.0 / 3.0, Some("%"), "3.3333%")]
+	#[test_case(-0.00001, Some("px"), "0px")]
+	fn display(number: f64, unit: Option<&str>, expected: &str) {
+		assert_eq!(Value::new(number, unit.map(String::from)).to_string(), expected);
+	}
+
+	#[test_case(1.0, "s", "ms", Some("1000ms") ; "seconds to milliseconds")]
+	#[test_case(250.0, "ms", "s", Some("0.25s") ; "milliseconds to seconds")]
+	#[test_case(1.0, "in", "px", Some("96px") ; "inches to pixels")]
+	#[test_case(0.5, "turn", "deg", Some("180deg") ; "turns to degrees")]
+	#[test_case(1.0, "rem", "px", None ; "relative length")]
+	#[test_case(1.0, "s", "px", None ; "different types")]
+	fn convert(number: f64, from: &str, to: &str, expected: Option<&str>) {
+		let value = Value::new(number, Some(from.to_string()));
+		assert_eq!(value.convert(&Some(to.to_string())).map(|v| v.to_string()), expected.map(String::from));
+	}
+}
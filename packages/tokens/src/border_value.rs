@@ -0,0 +1,47 @@
+use serde_derive::{Serialize, Deserialize};
+
+use crate::replace_method::ReplaceMethod;
+use crate::token_kind::TokenKind;
+use crate::token_store::TokenStore;
+use crate::token_value::TokenValue;
+use crate::utils::{references, string_or_number};
+
+/// Figma Token Studio provides Border token values as Objects with a predictable schema, similarly to shadow tokens.
+/// Each field may hold a static value or a reference to another token.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BorderValue {
+    pub(crate) color: String,
+    #[serde(deserialize_with = "string_or_number")]
+    pub(crate) width: String,
+    pub(crate) style: String,
+}
+
+impl BorderValue {
+    /// The names of the tokens referenced by any of the fields.
+    pub fn references(&self) -> Vec<String> {
+        [&self.color, &self.width, &self.style].into_iter().flat_map(|field| references(field)).collect()
+    }
+
+    /// Writes the border as the value of the `border` shorthand, i.e. `1px solid rgb(0, 0, 0)`
+    pub fn resolve(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+        let [width, style, color] = self.fields(store, replace_method, theme);
+
+        format!("{} {} {}", width, style, color)
+    }
+
+    /// Writes the border as an object, with each field resolved.
+    pub fn to_json(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> serde_json::Value {
+        let [width, style, color] = self.fields(store, replace_method, theme);
+
+        serde_json::json!({ "color": color, "width": width, "style": style })
+    }
+
+    /// Resolves the width, style and color of the border.
+    fn fields(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> [String; 3] {
+        [
+            TokenValue::parse(&self.width, TokenKind::BorderWidth).with_default_unit(TokenKind::BorderWidth).resolve(store, replace_method, theme),
+            TokenValue::parse(&self.style, TokenKind::Other).resolve(store, replace_method, theme),
+            TokenValue::parse(&self.color, TokenKind::Color).resolve_color(store, replace_method, theme),
+        ]
+    }
+}
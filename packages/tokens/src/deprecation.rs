@@ -0,0 +1,81 @@
+use serde::{Deserialize, Deserializer};
+use serde_derive::Serialize;
+
+/// Marks a token as deprecated, i.e. after it's been renamed, so that it keeps working for a while but can be moved away
+/// from. Written as `"deprecated": true`, a message (`"deprecated": "Use color.text.primary"`) or an object with the
+/// token that replaces it, i.e. `"deprecated": { "replacement": "color.text.primary", "message": "Renamed in v2" }`
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Deprecation {
+    /// The name of the token that replaces the deprecated token, i.e. `color.text.primary`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl Deprecation {
+    /// A short note on the deprecation, i.e. `Deprecated, use color.text.primary instead. Renamed in v2`
+    pub fn note(&self) -> String {
+        let mut note = String::from("Deprecated");
+
+        if let Some(replacement) = &self.replacement {
+            note.push_str(&format!(", use {} instead.", replacement));
+        } else {
+            note.push('.');
+        }
+
+        if let Some(message) = &self.message {
+            note.push_str(&format!(" {}", message));
+        }
+
+        note
+    }
+}
+
+/// Deserializes the `deprecated` (or DTCG `$deprecated`) field of a token, see `Deprecation`
+pub(crate) fn deprecation<'de, D>(deserializer: D) -> Result<Option<Deprecation>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // The replacement may be written as a reference, i.e. `{color.text.primary}`
+    let name = |value: &serde_json::Value| value.as_str().map(|s| s.trim().trim_start_matches('{').trim_end_matches('}').to_string());
+
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Bool(deprecated) => Ok(deprecated.then(Deprecation::default)),
+        serde_json::Value::String(message) => Ok(Some(Deprecation { replacement: None, message: Some(message) })),
+        serde_json::Value::Object(object) => Ok(Some(Deprecation {
+            replacement: object.get("replacement").and_then(name),
+            message: object.get("message").and_then(|m| m.as_str()).map(String::from),
+        })),
+        serde_json::Value::Null => Ok(None),
+        value => Err(serde::de::Error::custom(format!("expected a boolean, string or object, found {}", value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use test_case::test_case;
+
+    #[derive(serde_derive::Deserialize)]
+    struct Token {
+        #[serde(default, deserialize_with = "deprecation")]
+        deprecated: Option<Deprecation>,
+    }
+
+    #[test_case(json!({}), None ; "missing")]
+    #[test_case(json!({ "deprecated": false }), None ; "not deprecated")]
+    #[test_case(json!({ "deprecated": true }), Some("Deprecated.") ; "deprecated")]
+    #[test_case(json!({ "deprecated": "Use the brand color" }), Some("Deprecated. Use the brand color") ; "message")]
+    #[test_case(
+        json!({ "deprecated": { "replacement": "{color.text.primary}", "message": "Renamed in v2" } }),
+        Some("Deprecated, use color.text.primary instead. Renamed in v2")
+        ; "replacement"
+    )]
+    fn deserializes(value: serde_json::Value, expected: Option<&str>) {
+        let token: Token = serde_json::from_value(value).unwrap();
+
+        assert_eq!(token.deprecated.map(|d| d.note()), expected.map(String::from));
+    }
+}
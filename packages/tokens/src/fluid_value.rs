@@ -0,0 +1,213 @@
+use css_math::Value;
+
+use crate::replace_method::ReplaceMethod;
+use crate::token_kind::TokenKind;
+use crate::token_store::TokenStore;
+use crate::token_value::TokenValue;
+
+/// The viewport widths (in pixels) a fluid value scales between when the token doesn't set its own.
+const MIN_VIEWPORT: f64 = 320.0;
+const MAX_VIEWPORT: f64 = 1280.0;
+
+/// A dimension that scales linearly with the width of the viewport, from its min value at the min viewport width to
+/// its max value at the max viewport width. Declared with the `figtok.fluid` extension on a dimension token, i.e.
+/// `"$extensions": { "figtok": { "fluid": { "min": "16px", "max": "24px", "minViewport": 320, "maxViewport": 1280 } } }`,
+/// or by a group holding a `min` and `max` token (i.e. `font.size.body.min` and `font.size.body.max`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FluidValue {
+    pub(crate) min: TokenValue,
+    pub(crate) max: TokenValue,
+    pub(crate) min_viewport: f64,
+    pub(crate) max_viewport: f64,
+}
+
+impl FluidValue {
+    /// Reads the fluid extension of a token, the min and max may be static values or references to other tokens, and
+    /// are parsed as the kind of token the extension belongs to. A value can't scale without both, so if either is
+    /// missing the one that's present is returned as the error instead (or nothing, if neither is.)
+    pub fn from_json(value: &serde_json::Value, kind: TokenKind) -> Result<Self, Option<TokenValue>> {
+        let field = |key: &str| value.get(key).filter(|v| !v.is_null()).map(|v| TokenValue::from_json(v, kind));
+        let viewport = |key: &str, default: f64| {
+            value
+                .get(key)
+                .and_then(|v| v.as_f64().or_else(|| v.as_str()?.trim_end_matches("px").parse().ok()))
+                .unwrap_or(default)
+        };
+
+        match (field("min"), field("max")) {
+            (Some(min), Some(max)) => Ok(FluidValue {
+                min,
+                max,
+                min_viewport: viewport("minViewport", MIN_VIEWPORT),
+                max_viewport: viewport("maxViewport", MAX_VIEWPORT),
+            }),
+            (min, max) => Err(max.or(min)),
+        }
+    }
+
+    /// Writes the value as `clamp(min, preferred, max)`, where the preferred value is the line between the min and max
+    /// across the viewport range, i.e. `clamp(16px, 13.3333px + 0.8333vw, 24px)`. The slope has to be known ahead of
+    /// time, so the min and max are always resolved to static values to work it out, although they're written using the
+    /// given replace method. If the slope can't be worked out the max is written on its own, see `FluidValue::preferred`
+    pub fn resolve(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+        let max = resolve(&self.max, store, replace_method, theme);
+
+        match self.preferred(store, theme) {
+            Ok(preferred) => format!("clamp({}, {}, {})", resolve(&self.min, store, replace_method, theme), preferred, max),
+            Err(_) => max,
+        }
+    }
+
+    /// The preferred value of the `clamp()`, i.e. `13.3333px + 0.8333vw`, or why it can't be worked out.
+    pub fn preferred(&self, store: &dyn TokenStore, theme: &Option<String>) -> Result<String, FluidFallback> {
+        let static_value = |value: &TokenValue| {
+            let resolved = resolve(value, store, ReplaceMethod::StaticValues, theme);
+            length(&resolved).ok_or(FluidFallback::Unresolved(resolved))
+        };
+        let min_value = static_value(&self.min)?;
+        let max_value = static_value(&self.max)?;
+        let mixed_units = || FluidFallback::MixedUnits(min_value.to_string(), max_value.to_string());
+
+        // The viewport widths are given in px, so are converted to the unit of the min and max to work out the slope.
+        let px = match min_value.unit.as_deref() {
+            Some("rem") => 1.0 / store.base_font_size(),
+            _ => Value::new(1.0, Some(String::from("px"))).convert(&min_value.unit).ok_or_else(mixed_units)?.number,
+        };
+        let max_number = max_value.convert(&min_value.unit).ok_or_else(mixed_units)?.number;
+
+        let (min_viewport, max_viewport) = (self.min_viewport * px, self.max_viewport * px);
+        if max_viewport == min_viewport {
+            return Err(FluidFallback::EmptyViewportRange);
+        }
+
+        let slope = (max_number - min_value.number) / (max_viewport - min_viewport);
+        let intercept = min_value.number - slope * min_viewport;
+
+        let vw = Value::new(slope * 100.0, Some(String::from("vw")));
+        Ok(match intercept {
+            0.0 => vw.to_string(),
+            i if i < 0.0 => format!("{} - {}", vw, Value::new(-i, min_value.unit.clone())),
+            i => format!("{} + {}", Value::new(i, min_value.unit.clone()), vw),
+        })
+    }
+}
+
+/// Why a fluid value can't be written as `clamp()`, in which case it's written as its max value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FluidFallback {
+    /// The min or max doesn't resolve to a length, i.e. it's a broken reference, holding the value it resolved to.
+    Unresolved(String),
+    /// The min and max are lengths in units that can't be converted to each other, i.e. `16px` and `2em`
+    MixedUnits(String, String),
+    /// The min and max viewport widths are the same, so there's no line between them.
+    EmptyViewportRange,
+}
+
+impl std::fmt::Display for FluidFallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FluidFallback::Unresolved(value) => write!(f, "{} is not a length", value),
+            FluidFallback::MixedUnits(min, max) => write!(f, "the units of {} and {} can't be compared", min, max),
+            FluidFallback::EmptyViewportRange => write!(f, "the min and max viewport widths are the same"),
+        }
+    }
+}
+
+impl std::fmt::Display for FluidValue {
+    /// A fluid value can only be written as `clamp()` once its min and max are resolved, see `FluidValue::resolve`, so
+    /// on its own it's written as its max.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.max)
+    }
+}
+
+/// Resolves the min or max of a fluid value, unitless numbers are lengths in px.
+fn resolve(value: &TokenValue, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+    value.with_default_unit(TokenKind::Dimension).resolve(store, replace_method, theme)
+}
+
+/// Reads a resolved static value as a length, i.e. `16px` or `1.5rem`
+fn length(value: &str) -> Option<Value> {
+    match TokenValue::parse(value, TokenKind::Dimension) {
+        TokenValue::Dimension(number, unit) => Some(Value::new(number, Some(unit))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use crate::token::Token;
+    use crate::token_definition::TokenDefinition;
+    use crate::token_store::test_utils::MockStore;
+    use serde_json::json;
+    use test_case::test_case;
+
+    #[test_case(json!({ "min": 16, "max": 24 }), "clamp(16px, 13.3333px + 0.8333vw, 24px)" ; "unitless")]
+    #[test_case(json!({ "min": "1rem", "max": "2rem", "minViewport": 320, "maxViewport": "960px" }), "clamp(1rem, 0.5rem + 2.5vw, 2rem)" ; "rem")]
+    #[test_case(json!({ "min": "0px", "max": "64px", "minViewport": 0, "maxViewport": 1280 }), "clamp(0px, 5vw, 64px)" ; "no intercept")]
+    #[test_case(json!({ "min": "16px", "max": "48px", "minViewport": 640, "maxViewport": 1280 }), "clamp(16px, 5vw - 16px, 48px)" ; "negative intercept")]
+    #[test_case(json!({ "min": "16px", "max": "2rem" }), "2rem" ; "mixed units")]
+    fn clamps(value: serde_json::Value, expected: &str) {
+        let fluid = FluidValue::from_json(&value, TokenKind::FontSize).unwrap();
+
+        assert_eq!(fluid.resolve(&MockStore::default(), ReplaceMethod::StaticValues, &None), expected);
+    }
+
+    #[test_case(json!({ "min": "16px", "max": "2rem" }), FluidFallback::MixedUnits(String::from("16px"), String::from("2rem")) ; "mixed units")]
+    #[test_case(json!({ "min": "{font.size.missing}", "max": "24px" }), FluidFallback::Unresolved(String::from("BROKEN_REF")) ; "broken reference")]
+    #[test_case(json!({ "min": "16px", "max": "24px", "minViewport": 640, "maxViewport": 640 }), FluidFallback::EmptyViewportRange ; "empty viewport range")]
+    fn falls_back(value: serde_json::Value, expected: FluidFallback) {
+        let fluid = FluidValue::from_json(&value, TokenKind::FontSize).unwrap();
+
+        assert_eq!(fluid.preferred(&MockStore::default(), &None), Err(expected));
+    }
+
+    #[test_case(json!({ "min": "16px" }), Some(TokenValue::Dimension(16.0, String::from("px"))) ; "missing max")]
+    #[test_case(json!({ "min": null, "max": 24 }), Some(TokenValue::Number(24.0)) ; "null min")]
+    #[test_case(json!({ "minViewport": 320 }), None ; "neither")]
+    fn requires_min_and_max(value: serde_json::Value, present: Option<TokenValue>) {
+        assert_eq!(FluidValue::from_json(&value, TokenKind::FontSize), Err(present));
+    }
+
+    #[test]
+    fn rem_is_relative_to_the_base_font_size() {
+        let store = MockStore { base_font_size: 20.0, ..MockStore::default() };
+        let fluid = FluidValue::from_json(&json!({ "min": "1rem", "max": "2rem", "minViewport": 320, "maxViewport": 960 }), TokenKind::FontSize).unwrap();
+
+        assert_eq!(fluid.resolve(&store, ReplaceMethod::StaticValues, &None), "clamp(1rem, 0.5rem + 3.125vw, 2rem)");
+    }
+
+    #[test]
+    fn references() {
+        let tokens = [("font.size.md", "16px"), ("font.size.xl", "32px")]
+            .into_iter()
+            .map(|(name, value)| {
+                let token = Token::Standard(
+                    TokenDefinition {
+                        id: name.to_string(),
+                        name: name.to_string(),
+                        value: value.to_string(),
+                        kind: TokenKind::FontSize,
+                        description: None,
+                        extensions: None,
+                        deprecated: None,
+                    }
+                    .into(),
+                );
+                (token.id(), token)
+            })
+            .collect::<HashMap<String, Token>>();
+        let store = MockStore::new(tokens, HashMap::new(), HashMap::new());
+
+        let fluid = FluidValue::from_json(&json!({ "min": "{font.size.md}", "max": "{font.size.xl}" }), TokenKind::FontSize).unwrap();
+
+        assert_eq!(fluid.resolve(&store, ReplaceMethod::StaticValues, &None), "clamp(16px, 10.6667px + 1.6667vw, 32px)");
+        assert_eq!(
+            fluid.resolve(&store, ReplaceMethod::CssVariables, &None),
+            "clamp(var(--font-size-md), 10.6667px + 1.6667vw, var(--font-size-xl))"
+        );
+    }
+}
@@ -5,6 +5,10 @@ extern crate serde_derive;
 extern crate serde_json;
 
 // Internal Modules
+mod border_value;
+mod deprecation;
+mod fluid_value;
+mod naming;
 mod replace_method;
 mod shadow_value;
 mod token;
@@ -12,18 +16,28 @@ mod token_set;
 mod token_store;
 mod token_definition;
 mod token_kind;
+mod token_value;
+mod transition_value;
+mod typography_value;
 
 // Public Modules
 pub mod regex;
 pub mod utils;
 
 // "Exports"
+pub use border_value::BorderValue;
+pub use deprecation::Deprecation;
+pub use fluid_value::{FluidValue, FluidFallback};
+pub use naming::{NameCase, Naming, Replacement};
 pub use shadow_value::ShadowValue;
 pub use token::Token;
 pub use token_set::TokenSet;
 pub use token_store::TokenStore;
 pub use token_definition::TokenDefinition;
 pub use token_kind::TokenKind;
+pub use token_value::TokenValue;
+pub use transition_value::TransitionValue;
+pub use typography_value::TypographyValue;
 pub use replace_method::ReplaceMethod;
 
 // Type Aliases for Collections of Tokens.
@@ -0,0 +1,207 @@
+use std::str::FromStr;
+
+use convert_case::{Case, Casing};
+use regex::Regex;
+
+use crate::utils::{css_escape, css_escape_name, BOUNDARIES};
+
+/// The case the names of tokens are written in, i.e. `color-text-primary` (kebab), `colorTextPrimary` (camel),
+/// `color_text_primary` (snake), `ColorTextPrimary` (pascal) or `COLOR_TEXT_PRIMARY` (constant)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum NameCase {
+    #[default]
+    Kebab,
+    Camel,
+    Snake,
+    Pascal,
+    Constant,
+}
+
+impl FromStr for NameCase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "kebab" => Ok(NameCase::Kebab),
+            "camel" => Ok(NameCase::Camel),
+            "snake" => Ok(NameCase::Snake),
+            "pascal" => Ok(NameCase::Pascal),
+            "constant" => Ok(NameCase::Constant),
+            _ => Err(format!("expected one of kebab, camel, snake, pascal or constant, found \"{}\"", s)),
+        }
+    }
+}
+
+impl From<NameCase> for Case {
+    fn from(case: NameCase) -> Self {
+        match case {
+            NameCase::Kebab => Case::Kebab,
+            NameCase::Camel => Case::Camel,
+            NameCase::Snake => Case::Snake,
+            NameCase::Pascal => Case::Pascal,
+            NameCase::Constant => Case::UpperSnake,
+        }
+    }
+}
+
+/// Replaces every match of a regular expression in the path of a token, written as `FIND=REPLACE`, i.e. `^colour\.=color.`
+/// The replacement may refer to groups in the expression, i.e. `$1`. It's split from the expression at the last `=`, so
+/// the expression may contain `=` (i.e. `a=b=c` replaces `a=b` with `c`) but the replacement can't.
+#[derive(Debug, Clone)]
+pub struct Replacement {
+    pub find: Regex,
+    pub replace: String,
+}
+
+impl FromStr for Replacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (find, replace) = s.rsplit_once('=').ok_or_else(|| format!("expected FIND=REPLACE, found \"{}\"", s))?;
+        let find = Regex::new(find).map_err(|error| error.to_string())?;
+
+        Ok(Replacement { find, replace: replace.to_string() })
+    }
+}
+
+/// How the path of a token (i.e. `color.text.primary` in the `global` set) becomes the name it's written under, i.e.
+/// `--ds-color-text-primary`. The segments of the path are filtered and rewritten, then prefixed and finally cased.
+/// The default writes the path as it is, in kebab-case for CSS and unchanged for JSON.
+#[derive(Debug, Clone, Default)]
+pub struct Naming {
+    /// Written before the name of every token, i.e. `ds` for `--ds-color-text-primary`
+    pub prefix: Option<String>,
+    /// Whether the path is preceded by the name of the set the token belongs to. Set names are given as they are in the
+    /// ids of tokens, with nested sets separated by `.` (i.e. `core.brand` for the `core/brand` set), see `Token::set_name`
+    pub include_set: bool,
+    /// The case names are written in. CSS names are written in kebab-case if it isn't set, and JSON keys as they are.
+    pub case: Option<NameCase>,
+    /// Segments that are removed from the path wherever they appear, i.e. `global`
+    pub exclude: Vec<String>,
+    /// Applied in order to the path (joined with `.`), once the set name is included and segments are removed.
+    pub replacements: Vec<Replacement>,
+}
+
+impl Naming {
+    /// The name a token is written under as a custom property in CSS, without the leading `--`, i.e. `ds-color-text-primary`
+    pub fn css_name(&self, name: &str, set: Option<&str>) -> String {
+        css_escape_name(&self.cased(name, set))
+    }
+
+    /// The name a token is written under as a class in CSS, without the leading `.`. Unlike the name of a custom property,
+    /// a class can't start with a digit, so `100.gray` is written as `\31 00-gray`
+    pub fn css_class(&self, name: &str, set: Option<&str>) -> String {
+        css_escape(&self.cased(name, set))
+    }
+
+    fn cased(&self, name: &str, set: Option<&str>) -> String {
+        let case: Case = self.case.unwrap_or_default().into();
+
+        self.segments(name, set).join("-").with_boundaries(&BOUNDARIES).to_case(case)
+    }
+
+    /// The keys a token is nested under in JSON, i.e. `["ds", "color", "text", "primary"]`
+    pub fn json_path(&self, name: &str, set: Option<&str>) -> Vec<String> {
+        let segments = self.segments(name, set);
+
+        match self.case {
+            Some(case) => segments.iter().map(|s| s.with_boundaries(&BOUNDARIES).to_case(case.into())).collect(),
+            None => segments,
+        }
+    }
+
+    fn segments(&self, name: &str, set: Option<&str>) -> Vec<String> {
+        let set = set.filter(|_| self.include_set).into_iter().flat_map(|set| set.split('.').map(|s| s.trim()));
+
+        let path = set
+            .chain(name.split('.'))
+            .filter(|segment| !self.exclude.iter().any(|e| e == segment))
+            .collect::<Vec<&str>>()
+            .join(".");
+        let path = self
+            .replacements
+            .iter()
+            .fold(path, |path, r| r.find.replace_all(&path, r.replace.as_str()).to_string());
+
+        self.prefix
+            .iter()
+            .map(String::as_str)
+            .chain(path.split('.').filter(|segment| !segment.is_empty()))
+            .map(String::from)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    use crate::utils::css_stringify;
+
+    fn naming(case: Option<NameCase>) -> Naming {
+        Naming {
+            prefix: Some(String::from("ds")),
+            include_set: true,
+            case,
+            exclude: vec![String::from("core")],
+            replacements: vec!["colour=color".parse().unwrap(), "\\.(\\d+)$=.shade-$1".parse().unwrap()],
+        }
+    }
+
+    #[test_case("color.text.primary" ; "path")]
+    #[test_case("text.headings.h1.fontSize" ; "camel case")]
+    #[test_case("icon.size.1/2" ; "escaped")]
+    fn defaults_to_css_stringify(name: &str) {
+        assert_eq!(Naming::default().css_name(name, Some("global")), css_stringify(name));
+    }
+
+    #[test_case(None, "ds-brand-color-blue-shade-100" ; "default")]
+    #[test_case(Some(NameCase::Kebab), "ds-brand-color-blue-shade-100" ; "kebab")]
+    #[test_case(Some(NameCase::Camel), "dsBrandColorBlueShade100" ; "camel")]
+    #[test_case(Some(NameCase::Snake), "ds_brand_color_blue_shade_100" ; "snake")]
+    #[test_case(Some(NameCase::Pascal), "DsBrandColorBlueShade100" ; "pascal")]
+    #[test_case(Some(NameCase::Constant), "DS_BRAND_COLOR_BLUE_SHADE_100" ; "constant")]
+    fn css_names(case: Option<NameCase>, expected: &str) {
+        assert_eq!(naming(case).css_name("colour.blue.100", Some("core.brand")), expected);
+    }
+
+    #[test]
+    fn escapes_leading_digits_of_classes() {
+        assert_eq!(Naming::default().css_name("100.gray", None), "100-gray");
+        assert_eq!(Naming::default().css_class("100.gray", None), "\\31 00-gray");
+    }
+
+    #[test_case(None, vec!["ds", "brand", "color", "blue", "shade-100"] ; "as written")]
+    #[test_case(Some(NameCase::Camel), vec!["ds", "brand", "color", "blue", "shade100"] ; "camel")]
+    fn json_paths(case: Option<NameCase>, expected: Vec<&str>) {
+        assert_eq!(naming(case).json_path("colour.blue.100", Some("core.brand")), expected);
+    }
+
+    #[test]
+    fn replacements_apply_in_order() {
+        let naming = Naming {
+            replacements: vec!["colour=color".parse().unwrap(), "^color\\.=".parse().unwrap()],
+            ..Naming::default()
+        };
+
+        assert_eq!(naming.css_name("colour.text.primary", None), "text-primary");
+    }
+
+    #[test]
+    fn splits_replacements_at_the_last_equals_sign() {
+        let replacement: Replacement = "(?P<size>[a-z]+)=(\\d)=$size-$2".parse().unwrap();
+
+        assert_eq!(replacement.find.as_str(), "(?P<size>[a-z]+)=(\\d)");
+        assert_eq!(replacement.replace, "$size-$2");
+
+        let naming = Naming { replacements: vec![replacement], ..Naming::default() };
+        assert_eq!(naming.css_name("ratio.wide=2", None), "ratio-wide-2");
+    }
+
+    #[test]
+    fn rejects_invalid_replacements() {
+        assert!("colour".parse::<Replacement>().is_err());
+        assert!("colour(=color".parse::<Replacement>().is_err());
+    }
+}
@@ -6,6 +6,12 @@ pub static REGEX_HB: Lazy<Regex> = Lazy::new(|| {
 	Regex::new(r"\{([x[^\{\}]]*)\}.*?").unwrap()
 });
 
+/// Stores a Regex to find pixel values ( i.e. 16px or -0.5px ), that aren't part of a longer word such as a variable
+/// name or url. The character before the value is captured too, as the regex crate doesn't support lookbehind.
+pub static REGEX_PX: Lazy<Regex> = Lazy::new(|| {
+	Regex::new(r"(^|[^\w.-])(-?\d*\.?\d+)px\b").unwrap()
+});
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -1,27 +1,104 @@
 use serde_derive::{Serialize, Deserialize};
 
+use crate::replace_method::ReplaceMethod;
+use crate::token_kind::TokenKind;
+use crate::token_store::TokenStore;
+use crate::token_value::TokenValue;
+use crate::utils::{references, string_or_number};
+
 /// Figma Token Studio provides Shadow token values as Objects (similarly to a composition token)
 /// However, unlike a composition token they have a predictable schema that we can build a struct from.
 /// ShadowValue stores these values as a Vec of `ShadowLayer` structs that can be either a drop shadow
-/// or an inner shadow.
+/// or an inner shadow. Shadows with a single layer are written as one object rather than an array.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(from = "ShadowLayers")]
 pub struct ShadowValue(pub Vec<ShadowLayer>);
 
+/// A single layer of a shadow, written either as Tokens Studio writes it (`x`, `y` and a `type`) or as the DTCG format
+/// does (`offsetX`, `offsetY` and an optional `inset` flag.)
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ShadowLayer {
     pub(crate) color: String,
-    #[serde(alias = "type")]
+    #[serde(alias = "type", default)]
     pub(crate) kind: ShadowLayerKind,
+    #[serde(default, skip_serializing)]
+    pub(crate) inset: bool,
+    #[serde(alias = "offsetX", deserialize_with = "string_or_number")]
     pub(crate) x: String,
+    #[serde(alias = "offsetY", deserialize_with = "string_or_number")]
     pub(crate) y: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub(crate) blur: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub(crate) spread: String,
+    /// Figma can blend each layer of a shadow with what's beneath it. There's no equivalent for a single layer of a
+    /// `box-shadow` in CSS, so the blend mode is kept with the token but not written to the variable.
+    #[serde(rename = "blendMode", default, skip_serializing_if = "Option::is_none")]
+    pub(crate) blend_mode: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub enum ShadowLayerKind {
     #[serde(alias = "innerShadow")]
     InnerShadow,
     #[serde(alias = "dropShadow")]
+    #[default]
     DropShadow,
 }
+
+/// The shapes a shadow value can be written in, either a list of layers or a single layer on its own.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ShadowLayers {
+    Many(Vec<ShadowLayer>),
+    One(ShadowLayer),
+}
+
+impl From<ShadowLayers> for ShadowValue {
+    fn from(layers: ShadowLayers) -> Self {
+        match layers {
+            ShadowLayers::Many(layers) => ShadowValue(layers),
+            ShadowLayers::One(layer) => ShadowValue(vec![layer]),
+        }
+    }
+}
+
+impl ShadowValue {
+    /// The names of the tokens referenced by any of the fields of any layer.
+    pub fn references(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .flat_map(|layer| [&layer.color, &layer.x, &layer.y, &layer.blur, &layer.spread])
+            .flat_map(|field| references(field))
+            .collect()
+    }
+
+    /// Writes the shadow as the value of the `box-shadow` property, i.e. `0px 4px 8px 0px rgb(0, 0, 0)`
+    pub fn resolve(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+        // Unitless numbers are taken as pixels (as they are in Figma), anything else (explicit units, references or
+        // math) is resolved as it was written.
+        let dimension = |value: &str| TokenValue::parse(value, TokenKind::Dimension).with_default_unit(TokenKind::Dimension).resolve(store, replace_method, theme);
+
+        // The shadow is a Vec<ShadowLayer>, we format each of these as a valid CSS
+        // shadow, resolving the references in each field independently, and join the layers with a comma.
+        self.0
+            .iter()
+            .map(|layer| {
+                let shadow = format!(
+                    "{} {} {} {} {}",
+                    dimension(&layer.x),
+                    dimension(&layer.y),
+                    dimension(&layer.blur),
+                    dimension(&layer.spread),
+                    TokenValue::parse(&layer.color, TokenKind::Color).resolve_color(store, replace_method, theme)
+                );
+
+                match (&layer.kind, layer.inset) {
+                    (ShadowLayerKind::DropShadow, false) => shadow,
+                    _ => format!("inset {}", shadow),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
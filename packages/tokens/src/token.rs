@@ -1,14 +1,17 @@
-use std::collections::HashMap;
-
-use css_math;
 use serde_json::json;
 use convert_case::{Case, Casing};
 
+use crate::border_value::BorderValue;
 use crate::token_definition::TokenDefinition;
+use crate::token_kind::TokenKind;
+use crate::token_value::TokenValue;
+use crate::transition_value::TransitionValue;
+use crate::typography_value::TypographyValue;
 use crate::shadow_value::ShadowValue;
 use crate::replace_method::ReplaceMethod;
 use crate::token_store::TokenStore;
-use crate::utils::css_stringify;
+use crate::deprecation::Deprecation;
+use crate::utils::json_references;
 
 /// The Token enum holds a TokenDefinition<T> and provides an abstraction with getters for the 
 /// properties of a Token (name, id, kind, value.)
@@ -18,11 +21,16 @@ use crate::utils::css_stringify;
 /// from a getter method. This powers the reference tokens, where a Tokens value is a handlebar-style
 /// reference to another token in the store.
 /// 
+// Tokens are loaded once and then only read, so the size of the larger variants isn't a concern.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum Token {
-    Standard(TokenDefinition<String>),
+    Standard(TokenDefinition<TokenValue>),
     Composition(TokenDefinition<serde_json::Value>),
     Shadow(TokenDefinition<ShadowValue>),
+    Typography(TokenDefinition<TypographyValue>),
+    Border(TokenDefinition<BorderValue>),
+    Transition(TokenDefinition<TransitionValue>),
 }
 impl Token {
 	/// Get the token name from the underlying TokenDefinition<T>
@@ -31,6 +39,9 @@ impl Token {
 			Token::Standard(t) => t.name.clone(),
 			Token::Composition(t) => t.name.clone(),
 			Token::Shadow(t) => t.name.clone(),
+			Token::Typography(t) => t.name.clone(),
+			Token::Border(t) => t.name.clone(),
+			Token::Transition(t) => t.name.clone(),
 		}
 	}
 	
@@ -40,9 +51,102 @@ impl Token {
 			Token::Standard(t) => t.id.clone(),
 			Token::Composition(t) => t.id.clone(),
 			Token::Shadow(t) => t.id.clone(),
+			Token::Typography(t) => t.id.clone(),
+			Token::Border(t) => t.id.clone(),
+			Token::Transition(t) => t.id.clone(),
+		}
+	}
+
+	/// Get the kind of the token from the underlying TokenDefinition<T>
+	pub fn kind(&self) -> TokenKind {
+		match self {
+			Token::Standard(t) => t.kind,
+			Token::Composition(t) => t.kind,
+			Token::Shadow(t) => t.kind,
+			Token::Typography(t) => t.kind,
+			Token::Border(t) => t.kind,
+			Token::Transition(t) => t.kind,
+		}
+	}
+
+	/// Get the description of the token, if the designer gave it one.
+	pub fn description(&self) -> Option<&str> {
+		match self {
+			Token::Standard(t) => t.description.as_deref(),
+			Token::Composition(t) => t.description.as_deref(),
+			Token::Shadow(t) => t.description.as_deref(),
+			Token::Typography(t) => t.description.as_deref(),
+			Token::Border(t) => t.description.as_deref(),
+			Token::Transition(t) => t.description.as_deref(),
+		}
+	}
+
+	/// Get the extension data attached to the token, as it was written in the original json.
+	pub fn extensions(&self) -> Option<&serde_json::Value> {
+		match self {
+			Token::Standard(t) => t.extensions.as_ref(),
+			Token::Composition(t) => t.extensions.as_ref(),
+			Token::Shadow(t) => t.extensions.as_ref(),
+			Token::Typography(t) => t.extensions.as_ref(),
+			Token::Border(t) => t.extensions.as_ref(),
+			Token::Transition(t) => t.extensions.as_ref(),
+		}
+	}
+
+	/// Get the deprecation of the token, if it's deprecated.
+	pub fn deprecation(&self) -> Option<&Deprecation> {
+		match self {
+			Token::Standard(t) => t.deprecated.as_ref(),
+			Token::Composition(t) => t.deprecated.as_ref(),
+			Token::Shadow(t) => t.deprecated.as_ref(),
+			Token::Typography(t) => t.deprecated.as_ref(),
+			Token::Border(t) => t.deprecated.as_ref(),
+			Token::Transition(t) => t.deprecated.as_ref(),
+		}
+	}
+
+	/// The names of the tokens this token references, found in each of the fields of its value.
+	pub fn references(&self) -> Vec<String> {
+		match self {
+			Token::Standard(t) => t.value.references(),
+			Token::Typography(t) => t.value.references(),
+			Token::Composition(t) => json_references(&t.value),
+			Token::Shadow(t) => t.value.references(),
+			Token::Border(t) => t.value.references(),
+			Token::Transition(t) => t.value.references(),
 		}
 	}
 
+	/// The name of the set the token belongs to (i.e. `global`), which prefixes its id.
+	pub fn set_name(&self) -> String {
+		let (id, name) = (self.id(), self.name());
+		id.strip_suffix(&name).and_then(|set| set.strip_suffix('.')).unwrap_or_default().to_string()
+	}
+
+	/// The name the token is written under as a custom property in CSS, without the leading `--`, see `Naming`
+	pub fn css_name(&self, store: &dyn TokenStore) -> String {
+		store.naming().css_name(&self.name(), Some(&self.set_name()))
+	}
+
+	/// The name the token is written under as a class in CSS, without the leading `.`, see `Naming`
+	pub fn css_class(&self, store: &dyn TokenStore) -> String {
+		store.naming().css_class(&self.name(), Some(&self.set_name()))
+	}
+
+	/// The keys the token is nested under in JSON, see `Naming`
+	pub fn json_path(&self, store: &dyn TokenStore) -> Vec<String> {
+		store.naming().json_path(&self.name(), Some(&self.set_name()))
+	}
+
+	/// Whether the token is private, marked with the `figtok.private` extension. Private tokens can be referenced by other
+	/// tokens, but aren't output themselves, so their values are inlined wherever they're referenced.
+	pub fn is_private(&self) -> bool {
+		self.extensions()
+			.and_then(|extensions| extensions.pointer("/figtok/private"))
+			.and_then(|private| private.as_bool())
+			.unwrap_or(false)
+	}
+
 	/// Get the token value. This method calls the get_value() method of a TokenDefinition<T>, we can impl a different 
 	/// get_value for each possible value of T that we want to support, ultimately producing a string containing the value
 	/// of the token.
@@ -51,91 +155,143 @@ impl Token {
 	/// Because of this, it's only ever called directly for Standard tokens and Shadow tokens. Composition tokens are processed
 	/// differently as they are serialized as CSS classes containing multiple properties, as appose to CSS Variables. 
     pub fn value(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, nested: bool, theme: &Option<String>) -> String {
-        let mut value = match self {
+        match self {
             Token::Standard(t) => t.get_value(store, replace_method, nested, theme),
             Token::Shadow(t) => t.get_value(store, replace_method, theme),
-            Token::Composition(t) => {
-				// Composition tokens are output as classes, containing properties for each inner value of the token.
-				// Because of this, below instead of calling get_value directly on the token, we get the token value as_object() and
-				// iterate through its members, enriching each inner value and writing it to a string.
-				let mut result = String::new();
-
-				for (key, value) in t.value.as_object().unwrap() {
-					// Here we call enrich directly as the inner values of a composition token are not tokens in their own right, 
-					//so don't already exist on store - but may still contain references to tokens.
-					let token_value = store.enrich(serde_json::from_value::<String>(value.to_owned()).unwrap(), replace_method, theme);
-					
-					result.push_str(
-					format!(
-							"{}: {};", 
-							key.replace(".", "-").to_case(Case::Kebab),
-							token_value
-						).as_str()
-					);
-				}
-
-				result
-			}, 
-        };
-
-		// Run the token through our css math tokenizer to check if we should
-		// wrap the string in calc() or not.
-		if css_math::is_css_math(&value) {
-			value = format!("calc({})", value);
-		}
-
-        value
+            Token::Border(t) => t.get_value(store, replace_method, theme),
+            Token::Transition(t) => t.get_value(store, replace_method, theme),
+            // Typography tokens are output as classes in the same way as composition tokens, but each field is mapped to
+            // the css property it represents.
+            Token::Typography(t) => t
+                .value
+                .properties(store, replace_method, theme)
+                .into_iter()
+                .map(|(property, value)| format!("{}: {};", property, value))
+                .collect(),
+            // Composition tokens are output as classes, containing properties for each inner value of the token.
+            Token::Composition(t) => composition_properties(&t.value, store, replace_method, theme)
+                .into_iter()
+                .map(|(property, value)| format!("{}: {};", property, value))
+                .collect(),
+        }
     }
 
 	pub fn serialize(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+		self.serialize_with(store, replace_method, theme, |_, value| value)
+	}
+
+	/// Serializes the token in the same way as `serialize`, passing each value through `map` before it's written along with
+	/// the css property it belongs to (for variables, the property of the token's kind i.e. `border-width`)
+	pub fn serialize_with(
+		&self,
+		store: &dyn TokenStore,
+		replace_method: ReplaceMethod,
+		theme: &Option<String>,
+		map: impl Fn(&str, String) -> String,
+	) -> String {
 		match self {
-			Token::Standard(_) | Token::Shadow(_) => {
+			Token::Standard(_) | Token::Shadow(_) | Token::Border(_) | Token::Transition(_) => {
 				format!(
 					"--{}: {};",
-					css_stringify(&self.name()),
-					self.value(store, replace_method, false, theme)
+					self.css_name(store),
+					map(&self.kind().to_string(), self.value(store, replace_method, false, theme))
 				)
 			}
-			Token::Composition(_) => {
-				let selector_name = &css_stringify(&self.name());
-				format!(
-					".{} {{{}}}", 
-					selector_name, 
-					&self.value(store, replace_method, false, theme)
-				)
+			Token::Composition(_) | Token::Typography(_) => {
+				let selector_name = &self.css_class(store);
+				let properties: String = self
+					.properties(store, replace_method, theme)
+					.unwrap_or_default()
+					.into_iter()
+					.map(|(property, value)| {
+						let value = map(&property, value);
+						format!("{}: {};", property, value)
+					})
+					.collect();
+
+				format!(".{} {{{}}}", selector_name, properties)
 			},
 		}
 	}
 	
+	/// Serializes a typography token as a css variable holding the `font` shorthand, i.e. `--heading-1: 700 32px/1.2 Inter;`.
+	/// Returns `None` for any other kind of token, or if the shorthand can't be written.
+	pub fn serialize_font_shorthand(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> Option<String> {
+		self.serialize_font_shorthand_with(store, replace_method, theme, |_, value| value)
+	}
+
+	/// Serializes the `font` shorthand in the same way as `serialize_font_shorthand`, passing the value through `map` as
+	/// `serialize_with` does.
+	pub fn serialize_font_shorthand_with(
+		&self,
+		store: &dyn TokenStore,
+		replace_method: ReplaceMethod,
+		theme: &Option<String>,
+		map: impl Fn(&str, String) -> String,
+	) -> Option<String> {
+		match self {
+			Token::Typography(t) => t
+				.value
+				.font_shorthand(store, replace_method, theme)
+				.map(|font| format!("--{}: {};", self.css_name(store), map("font", font))),
+			_ => None,
+		}
+	}
+
 	pub fn to_json(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> serde_json::Value {
 		match &self {
 			Token::Standard(_) | Token::Shadow(_) => {
-				let token_name = self.name();
-				let mut key_parts = token_name.split(".").collect::<Vec<&str>>();
+				let mut key_parts = self.json_path(store);
 				key_parts.reverse();
 
-				let value = self.value(store, replace_method, false, theme);
-				
-				let mut j = json!(value);
+				// Static values are written natively (i.e. numbers as numbers), anything that needs enriching (or, for fluid
+				// values, computing) is written as the resulting string.
+				let mut j = match self {
+					Token::Standard(t) if !t.value.has_references() && !matches!(t.value, TokenValue::Fluid(_)) => t.value.to_json(),
+					_ => json!(self.value(store, replace_method, false, theme)),
+				};
 				for key in key_parts {
 					j = json!({ key: j })
 				};
 
 				j
 			}
-			Token::Composition(t) => {
-				let token_name = self.name();
-				let mut key_parts = token_name.split(".").collect::<Vec<&str>>();
+			Token::Typography(t) => {
+				let mut key_parts = self.json_path(store);
+				key_parts.reverse();
+
+				let mut j = t.value.to_json(store, replace_method, theme);
+				for key in key_parts {
+					j = json!({ key: j })
+				}
+
+				j
+			}
+			Token::Border(_) | Token::Transition(_) => {
+				let mut key_parts = self.json_path(store);
 				key_parts.reverse();
 
-				let mut properties: HashMap<String, String> = HashMap::new();
+				let mut j = match self {
+					Token::Border(t) => t.to_json(store, replace_method, theme),
+					Token::Transition(t) => t.to_json(store, replace_method, theme),
+					_ => unreachable!(),
+				};
+				for key in key_parts {
+					j = json!({ key: j })
+				}
+
+				j
+			}
+			Token::Composition(t) => {
+				let mut key_parts = self.json_path(store);
+				key_parts.reverse();
 
-				for (property_name, property_value) in t.value.as_object().unwrap() {
-					let inner_value = store.enrich(serde_json::from_value::<String>(property_value.to_owned()).unwrap(), replace_method, &theme);
-					properties.insert(property_name.clone(), inner_value);
+				// Properties are written in camelCase, as they would be in a CSS-in-JS style object.
+				let mut j = json!({});
+				for (property, value) in composition_properties(&t.value, store, replace_method, theme) {
+					j[property.to_case(Case::Camel)] = json!(value);
 				}
 
-				let mut j = serde_json::to_value(properties).unwrap();
 				for key in key_parts {
 					j = json!({ key: j })
 				}
@@ -144,16 +300,105 @@ impl Token {
 			}
 		}
 	}
+
+	/// The CSS properties this token is written as when it's referenced by a composition token. Typography and composition
+	/// tokens are spread into each of their properties, while border, shadow and transition tokens are a single shorthand
+	/// property. Standard tokens hold a single value rather than properties, so return `None`.
+	fn properties(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> Option<Vec<(String, String)>> {
+		let shorthand = |kind: &TokenKind| vec![(kind.to_string(), store.enrich(format!("{{{}}}", self.name()), replace_method, theme))];
+
+		match self {
+			Token::Standard(_) => None,
+			Token::Composition(t) => Some(composition_properties(&t.value, store, replace_method, theme)),
+			Token::Typography(t) => Some(
+				t.value
+					.properties(store, replace_method, theme)
+					.into_iter()
+					.map(|(property, value)| (property.to_string(), value))
+					.collect(),
+			),
+			Token::Shadow(t) => Some(shorthand(&t.kind)),
+			Token::Border(t) => Some(shorthand(&t.kind)),
+			Token::Transition(t) => Some(shorthand(&t.kind)),
+		}
+	}
+}
+
+/// Resolves the properties of a composition token, in the order they're written. The value of a property (or the whole
+/// composition) may be a reference to another composite token, i.e. `"typography": "{heading.lg}"`, in which case its
+/// properties are spread into the composition. Properties written later override any earlier property of the same name.
+fn composition_properties(value: &serde_json::Value, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> Vec<(String, String)> {
+	let mut properties: Vec<(String, String)> = vec![];
+	let mut set = |property: String, value: String| {
+		properties.retain(|(p, _)| *p != property);
+		properties.push((property, value));
+	};
+
+	// The token a value references, if the value is nothing but a reference.
+	let referenced = |value: &str| match TokenValue::parse(value, TokenKind::Other) {
+		TokenValue::Reference(name) => store.tokens(theme).into_iter().find(|t| t.name() == name),
+		_ => None,
+	};
+
+	match value {
+		serde_json::Value::String(value) => {
+			for (property, value) in referenced(value).and_then(|t| t.properties(store, replace_method, theme)).unwrap_or_default() {
+				set(property, value);
+			}
+		}
+		serde_json::Value::Object(map) => {
+			for (key, value) in map {
+				// Tokens holding several properties are spread into the composition, anything else (including border
+				// and shadow tokens) is the value of the property it's written against.
+				if let Some(t @ (Token::Composition(_) | Token::Typography(_))) = value.as_str().and_then(referenced) {
+					for (property, value) in t.properties(store, replace_method, theme).unwrap_or_default() {
+						set(property, value);
+					}
+					continue;
+				}
+
+				// The keys of a composition token are the types of token its values would be if they were defined on their
+				// own (i.e. `opacity` or `boxShadow`), so each value is parsed in the same way as a standalone token.
+				let property = key.replace(".", "-").to_case(Case::Kebab);
+				let kind = serde_json::from_value::<TokenKind>(json!(key)).unwrap_or(TokenKind::Other);
+
+				match (kind, value) {
+					(TokenKind::Typography, serde_json::Value::Object(_)) => {
+						for (property, value) in TypographyValue::from_json(value).properties(store, replace_method, theme) {
+							set(property.to_string(), value);
+						}
+					}
+					(TokenKind::BoxShadow | TokenKind::Border | TokenKind::Transition, serde_json::Value::Object(_) | serde_json::Value::Array(_)) => {
+						let resolved = match kind {
+							TokenKind::BoxShadow => serde_json::from_value::<ShadowValue>(value.clone()).map(|v| v.resolve(store, replace_method, theme)),
+							TokenKind::Border => serde_json::from_value::<BorderValue>(value.clone()).map(|v| v.resolve(store, replace_method, theme)),
+							_ => serde_json::from_value::<TransitionValue>(value.clone()).map(|v| v.resolve(store, replace_method, theme)),
+						};
+
+						// Objects that don't match the schema of the token type are passed through, rather than failing the build.
+						set(property, resolved.unwrap_or_else(|_| value.to_string()));
+					}
+					(TokenKind::Color, value) => set(property, TokenValue::from_json(value, kind).resolve_color(store, replace_method, theme)),
+					(kind, value) => set(property, TokenValue::from_json(value, kind).with_default_unit(kind).resolve(store, replace_method, theme)),
+				}
+			}
+		}
+		_ => {}
+	}
+
+	properties
 }
 
 #[cfg(test)]
 mod test {
 	use super::*;
 	use crate::token_store::test_utils::MockStore;
-	use crate::TokenKind;
+	use crate::utils::css_stringify;
+	use std::collections::HashMap;
 
 	mod value {
 		use super::*;
+		use test_case::test_case;
 
 		#[test]
 		fn standard() {
@@ -163,10 +408,13 @@ mod test {
 				id: String::from("global.typescale.4"),
 				name: String::from("typescale.4"),
 				value: String::from("24px"),
-				kind: TokenKind::Other
+				kind: TokenKind::Other,
+				description: None,
+				extensions: None,
+				deprecated: None,
 			};
 			
-			let token = Token::Standard(token_definition);
+			let token = Token::Standard(token_definition.into());
 
 			assert_eq!(token.value(&store, ReplaceMethod::CssVariables, false, &None), "24px".to_string());
 		}
@@ -179,12 +427,15 @@ mod test {
 				name: String::from("ref.grey.0"),
 				value: String::from("#000000"),
 				kind: TokenKind::Color,
+				description: None,
+				extensions: None,
+				deprecated: None,
 			};
 
 			// Init a HashMap to store token, that we'll pass along to MockStore.
 			let mut tokens = HashMap::new();
 			// Insert the reference token into the HashMap.
-			tokens.insert(ref_definition.id.clone(), Token::Standard(ref_definition));
+			tokens.insert(ref_definition.id.clone(), Token::Standard(ref_definition.into()));
 
 			// Initialize a MockStore with our tokens, and empty HashMap's for token_sets and themes.
 			let store = MockStore::new(tokens, HashMap::new(), HashMap::new());
@@ -195,10 +446,13 @@ mod test {
 				name: String::from("color.text"),
 				value: String::from("{ref.grey.0}"),
 				kind: TokenKind::Color,
+				description: None,
+				extensions: None,
+				deprecated: None,
 			};
 
 			// Create a Token from the token_definition, and get a reference to our ref_token in the store.
-			let token = Token::Standard(token_definition);
+			let token = Token::Standard(token_definition.into());
 			let ref_token = &store.token(&String::from("global.ref.grey.0"));
 
 			// Check the static replace method produces the expected output
@@ -217,6 +471,600 @@ mod test {
 			);
 		}
 
+		#[test]
+		fn math_reference() {
+			let ref_definition = TokenDefinition {
+				id: String::from("global.spacing.base"),
+				name: String::from("spacing.base"),
+				value: String::from("8px"),
+				kind: TokenKind::Spacing,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			};
+
+			let mut tokens = HashMap::new();
+			tokens.insert(ref_definition.id.clone(), Token::Standard(ref_definition.into()));
+
+			let store = MockStore::new(tokens, HashMap::new(), HashMap::new());
+
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.spacing.lg"),
+				name: String::from("spacing.lg"),
+				value: String::from("{spacing.base} * 2"),
+				kind: TokenKind::Spacing,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			// Static values can be computed ahead of time.
+			assert_eq!(token.value(&store, ReplaceMethod::StaticValues, false, &None), String::from("16px"));
+
+			// References to css variables must be left for the browser to compute.
+			assert_eq!(
+				token.value(&store, ReplaceMethod::CssVariables, false, &None),
+				String::from("calc(var(--spacing-base) * 2)")
+			);
+		}
+
+		#[test]
+		fn math_with_incompatible_units() {
+			let store = MockStore::default();
+
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.sizing.content"),
+				name: String::from("sizing.content"),
+				value: String::from("100% - 16px"),
+				kind: TokenKind::Sizing,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			assert_eq!(token.value(&store, ReplaceMethod::StaticValues, false, &None), String::from("calc(100% - 16px)"));
+		}
+
+		#[test]
+		fn math_without_whitespace() {
+			let store = MockStore::default();
+
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.sizing.content"),
+				name: String::from("sizing.content"),
+				value: String::from("{sizing.full}-{spacing.gutter}*2"),
+				kind: TokenKind::Sizing,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			assert_eq!(
+				token.value(&store, ReplaceMethod::CssVariables, false, &None),
+				String::from("calc(var(--sizing-full) - var(--spacing-gutter) * 2)")
+			);
+		}
+
+		#[test]
+		fn private_reference() {
+			let palette = TokenDefinition {
+				id: String::from("global._palette.blue"),
+				name: String::from("_palette.blue"),
+				value: String::from("#0000ff"),
+				kind: TokenKind::Color,
+				description: None,
+				extensions: Some(json!({ "figtok": { "private": true } })),
+				deprecated: None,
+			};
+
+			let mut tokens = HashMap::new();
+			tokens.insert(palette.id.clone(), Token::Standard(palette.into()));
+
+			let store = MockStore::new(tokens, HashMap::new(), HashMap::new());
+
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.color.primary"),
+				name: String::from("color.primary"),
+				value: String::from("{_palette.blue}"),
+				kind: TokenKind::Color,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			assert!(store.tokens["global._palette.blue"].is_private());
+			assert!(!token.is_private());
+			// Private tokens aren't output as variables, so are inlined rather than referenced.
+			assert_eq!(token.value(&store, ReplaceMethod::CssVariables, false, &None), String::from("rgb(0, 0, 255)"));
+		}
+
+		#[test]
+		fn naming() {
+			let primary = TokenDefinition {
+				id: String::from("brand.core.color.primary"),
+				name: String::from("color.primary"),
+				value: String::from("#0000ff"),
+				kind: TokenKind::Color,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			};
+
+			let mut tokens = HashMap::new();
+			tokens.insert(primary.id.clone(), Token::Standard(primary.into()));
+
+			let store = MockStore {
+				naming: crate::Naming {
+					prefix: Some(String::from("ds")),
+					include_set: true,
+					case: Some(crate::NameCase::Snake),
+					exclude: vec![String::from("core")],
+					replacements: vec![],
+				},
+				..MockStore::new(tokens, HashMap::new(), HashMap::new())
+			};
+
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.color.link"),
+				name: String::from("color.link"),
+				value: String::from("{color.primary}"),
+				kind: TokenKind::Color,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			// References are written under the same name as the token they reference.
+			assert_eq!(store.tokens["brand.core.color.primary"].serialize(&store, ReplaceMethod::CssVariables, &None), "--ds_brand_color_primary: 0, 0, 255;");
+			assert_eq!(token.serialize(&store, ReplaceMethod::CssVariables, &None), "--ds_global_color_link: rgb(var(--ds_brand_color_primary));");
+			assert_eq!(token.set_name(), "global");
+			assert_eq!(
+				token.to_json(&store, ReplaceMethod::StaticValues, &None),
+				json!({ "ds": { "global": { "color": { "link": "rgb(0, 0, 255)" } } } })
+			);
+		}
+
+		#[test]
+		fn math_function() {
+			let store = MockStore::default();
+
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.fontSize.fluid"),
+				name: String::from("fontSize.fluid"),
+				value: String::from("clamp(1rem, 2vw + 1rem, 3rem)"),
+				kind: TokenKind::FontSize,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			assert_eq!(
+				token.value(&store, ReplaceMethod::StaticValues, false, &None),
+				String::from("clamp(1rem, 2vw + 1rem, 3rem)")
+			);
+		}
+
+		#[test_case(TokenKind::Spacing, "16", "16px" ; "spacing")]
+		#[test_case(TokenKind::BorderRadius, "4", "4px" ; "border radius")]
+		#[test_case(TokenKind::Spacing, "1rem", "1rem" ; "explicit unit")]
+		#[test_case(TokenKind::Opacity, "0.5", "0.5" ; "opacity")]
+		#[test_case(TokenKind::FontWeights, "700", "700" ; "font weight")]
+		fn default_unit(kind: TokenKind, value: &str, expected: &str) {
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.token"),
+				name: String::from("token"),
+				value: String::from(value),
+				kind,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			assert_eq!(token.value(&MockStore::default(), ReplaceMethod::StaticValues, false, &None), String::from(expected));
+			// JSON output keeps the number as it was written.
+			assert_eq!(token.to_json(&MockStore::default(), ReplaceMethod::StaticValues, &None)["token"], TokenValue::parse(value, kind).to_json());
+		}
+
+		#[test]
+		fn math_exponent() {
+			let base = TokenDefinition {
+				id: String::from("global.fontSize.base"),
+				name: String::from("fontSize.base"),
+				value: String::from("16px"),
+				kind: TokenKind::FontSize,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			};
+			let ratio = TokenDefinition {
+				id: String::from("global.fontSize.ratio"),
+				name: String::from("fontSize.ratio"),
+				value: String::from("1.25"),
+				kind: TokenKind::Number,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			};
+
+			let mut tokens = HashMap::new();
+			tokens.insert(base.id.clone(), Token::Standard(base.into()));
+			tokens.insert(ratio.id.clone(), Token::Standard(ratio.into()));
+
+			let store = MockStore::new(tokens, HashMap::new(), HashMap::new());
+
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.fontSize.3"),
+				name: String::from("fontSize.3"),
+				value: String::from("{fontSize.base} * {fontSize.ratio} ^ 3"),
+				kind: TokenKind::FontSize,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			assert_eq!(token.value(&store, ReplaceMethod::StaticValues, false, &None), String::from("31.25px"));
+			assert_eq!(
+				token.value(&store, ReplaceMethod::CssVariables, false, &None),
+				String::from("calc(var(--font-size-base) * pow(var(--font-size-ratio), 3))")
+			);
+		}
+
+		#[test]
+		fn math_simplified() {
+			let store = MockStore::default();
+
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.spacing.section"),
+				name: String::from("spacing.section"),
+				value: String::from("((({spacing.base})*2)*4) + (2px*2)"),
+				kind: TokenKind::Spacing,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			assert_eq!(
+				token.value(&store, ReplaceMethod::CssVariables, false, &None),
+				String::from("calc(var(--spacing-base) * 8 + 4px)")
+			);
+		}
+
+		#[test]
+		fn typography() {
+			let store = MockStore::default();
+
+			let token = Token::Typography(TokenDefinition {
+				id: String::from("global.heading.1"),
+				name: String::from("heading.1"),
+				value: json!({ "fontFamily": "{font.family.heading}", "fontSize": "32px", "paragraphSpacing": "16px", "textCase": "uppercase" }),
+				kind: TokenKind::Typography,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			assert_eq!(
+				token.serialize(&store, ReplaceMethod::CssVariables, &None),
+				String::from(".heading-1 {font-family: var(--font-family-heading);font-size: 32px;margin-block-end: 16px;text-transform: uppercase;}")
+			);
+			assert_eq!(
+				token.serialize_font_shorthand(&store, ReplaceMethod::CssVariables, &None),
+				Some(String::from("--heading-1: 32px var(--font-family-heading);"))
+			);
+		}
+
+		#[test]
+		fn border() {
+			let ref_definition = TokenDefinition {
+				id: String::from("global.color.border"),
+				name: String::from("color.border"),
+				value: String::from("#000000"),
+				kind: TokenKind::Color,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			};
+
+			let mut tokens = HashMap::new();
+			tokens.insert(ref_definition.id.clone(), Token::Standard(ref_definition.into()));
+
+			let store = MockStore::new(tokens, HashMap::new(), HashMap::new());
+
+			let token = Token::Border(serde_json::from_value(json!({
+				"value": { "color": "{color.border}", "width": "1px", "style": "solid" },
+				"type": "border",
+				"name": "border.default",
+				"id": "global.border.default"
+			})).unwrap());
+
+			assert_eq!(
+				token.serialize(&store, ReplaceMethod::CssVariables, &None),
+				String::from("--border-default: 1px solid rgb(var(--color-border));")
+			);
+			assert_eq!(
+				token.value(&store, ReplaceMethod::StaticValues, false, &None),
+				String::from("1px solid rgb(0, 0, 0)")
+			);
+			assert_eq!(
+				token.to_json(&store, ReplaceMethod::StaticValues, &None),
+				json!({ "border": { "default": { "color": "rgb(0, 0, 0)", "width": "1px", "style": "solid" } } })
+			);
+		}
+
+		#[test]
+		fn composition_spreads_composite_references() {
+			let heading = Token::Typography(TokenDefinition {
+				id: String::from("global.heading.lg"),
+				name: String::from("heading.lg"),
+				value: json!({ "fontFamily": "Inter", "fontSize": "32px", "lineHeight": "120%" }),
+				kind: TokenKind::Typography,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+			let border = Token::Border(serde_json::from_value(json!({
+				"value": { "color": "#000000", "width": "1px", "style": "solid" },
+				"type": "border",
+				"name": "border.default",
+				"id": "global.border.default"
+			})).unwrap());
+			let card = Token::Composition(TokenDefinition {
+				id: String::from("global.card"),
+				name: String::from("card"),
+				value: json!({ "padding": "16px", "typography": "{heading.lg}", "lineHeight": "1" }),
+				kind: TokenKind::Composition,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			});
+
+			let tokens = [heading, border, card].into_iter().map(|t| (t.id(), t)).collect::<HashMap<String, Token>>();
+			let store = MockStore::new(tokens, HashMap::new(), HashMap::new());
+
+			let token = Token::Composition(TokenDefinition {
+				id: String::from("global.card.outlined"),
+				name: String::from("card.outlined"),
+				value: json!("{card}"),
+				kind: TokenKind::Composition,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			});
+			assert_eq!(
+				token.serialize(&store, ReplaceMethod::StaticValues, &None),
+				String::from(".card-outlined {padding: 16px;font-family: Inter;font-size: 32px;line-height: 1;}")
+			);
+
+			let token = Token::Composition(TokenDefinition {
+				id: String::from("global.card.bordered"),
+				name: String::from("card.bordered"),
+				value: json!({ "fill": "{card}", "borderTop": "{border.default}", "padding": "8px" }),
+				kind: TokenKind::Composition,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			});
+			assert_eq!(
+				token.serialize(&store, ReplaceMethod::CssVariables, &None),
+				String::from(".card-bordered {font-family: Inter;font-size: 32px;line-height: 1;border-top: var(--border-default);padding: 8px;}")
+			);
+			assert_eq!(
+				token.to_json(&store, ReplaceMethod::StaticValues, &None),
+				json!({ "card": { "bordered": {
+					"fontFamily": "Inter",
+					"fontSize": "32px",
+					"lineHeight": "1",
+					"borderTop": "1px solid rgb(0, 0, 0)",
+					"padding": "8px"
+				} } })
+			);
+		}
+
+		#[test]
+		fn composition_parses_typed_values() {
+			let store = MockStore::default();
+
+			let token = Token::Composition(TokenDefinition {
+				id: String::from("global.toast"),
+				name: String::from("toast"),
+				value: json!({
+					"opacity": 0.5,
+					"color": "#ffffff",
+					"spacing": "{spacing.base} * 2",
+					"boxShadow": { "x": 0, "y": 2, "blur": 4, "spread": 0, "color": "#000000", "type": "dropShadow" },
+					"typography": { "fontWeight": "Bold", "lineHeight": "150%" }
+				}),
+				kind: TokenKind::Composition,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			});
+
+			assert_eq!(
+				token.value(&store, ReplaceMethod::CssVariables, false, &None),
+				String::from(
+					"opacity: 0.5;color: rgb(255, 255, 255);spacing: calc(var(--spacing-base) * 2);box-shadow: 0px 2px 4px 0px rgb(0, 0, 0);font-weight: 700;line-height: 1.5;"
+				)
+			);
+		}
+
+		#[test]
+		fn shadow() {
+			let blur = TokenDefinition {
+				id: String::from("global.shadow.blur.md"),
+				name: String::from("shadow.blur.md"),
+				value: String::from("8px"),
+				kind: TokenKind::Dimension,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			};
+
+			let mut tokens = HashMap::new();
+			tokens.insert(blur.id.clone(), Token::Standard(blur.into()));
+
+			let store = MockStore::new(tokens, HashMap::new(), HashMap::new());
+
+			let token = Token::Shadow(serde_json::from_value(json!({
+				"value": [
+					{ "x": 0, "y": "4px", "blur": "{shadow.blur.md}", "spread": "-0.5rem", "color": "#000000", "type": "dropShadow" },
+					{ "x": "0", "y": "1", "blur": "2", "spread": "0", "color": "rgba(0, 0, 0, 0.1)", "type": "innerShadow" }
+				],
+				"type": "boxShadow",
+				"name": "shadow.md",
+				"id": "global.shadow.md"
+			})).unwrap());
+
+			assert_eq!(
+				token.serialize(&store, ReplaceMethod::CssVariables, &None),
+				String::from("--shadow-md: 0px 4px var(--shadow-blur-md) -0.5rem rgb(0, 0, 0), inset 0px 1px 2px 0px rgba(0, 0, 0, 0.1);")
+			);
+			assert_eq!(
+				token.value(&store, ReplaceMethod::StaticValues, false, &None),
+				String::from("0px 4px 8px -0.5rem rgb(0, 0, 0), inset 0px 1px 2px 0px rgba(0, 0, 0, 0.1)")
+			);
+		}
+
+		#[test]
+		fn references_of_composite_values() {
+			let token = |value: serde_json::Value| -> Token {
+				let kind: TokenKind = serde_json::from_value(value["type"].clone()).unwrap();
+				match kind {
+					TokenKind::BoxShadow => Token::Shadow(serde_json::from_value(value).unwrap()),
+					TokenKind::Border => Token::Border(serde_json::from_value(value).unwrap()),
+					TokenKind::Transition => Token::Transition(serde_json::from_value(value).unwrap()),
+					_ => Token::Composition(serde_json::from_value(value).unwrap()),
+				}
+			};
+
+			let shadow = token(json!({ "value": { "x": 0, "y": 4, "blur": 8, "spread": 0, "color": "#000000", "type": "dropShadow" }, "type": "boxShadow" }));
+			let border = token(json!({ "value": { "color": "#000000", "width": 1, "style": "solid" }, "type": "border" }));
+			let transition = token(json!({ "value": { "duration": "200ms", "timingFunction": [0.4, 0, 0.2, 1] }, "type": "transition" }));
+			let composition = token(json!({ "value": { "spacing": "8px", "fill": "#ffffff" }, "type": "composition" }));
+			for token in [shadow, border, transition, composition] {
+				assert_eq!(token.references(), Vec::<String>::new());
+			}
+
+			let shadow = token(json!({ "value": [{ "x": 0, "y": "{space.sm}", "blur": 8, "spread": 0, "color": "{color.black}" }], "type": "shadow" }));
+			let border = token(json!({ "value": { "color": "{color.black}", "width": "{border.thin}", "style": "solid" }, "type": "border" }));
+			let transition = token(json!({ "value": { "duration": "{duration.fast}", "timingFunction": "{easing.out}" }, "type": "transition" }));
+			let composition = token(json!({ "value": { "spacing": "{space.sm}", "fill": "{color.white}" }, "type": "composition" }));
+
+			assert_eq!(shadow.references(), vec!["color.black", "space.sm"]);
+			assert_eq!(border.references(), vec!["color.black", "border.thin"]);
+			assert_eq!(transition.references(), vec!["duration.fast", "easing.out"]);
+			assert_eq!(composition.references(), vec!["space.sm", "color.white"]);
+		}
+
+		#[test]
+		fn dtcg_shadow() {
+			let token = Token::Shadow(serde_json::from_value(json!({
+				"value": [
+					{ "offsetX": "0px", "offsetY": "4px", "blur": "8px", "spread": "0px", "color": "#000000" },
+					{ "offsetX": 0, "offsetY": 1, "blur": 2, "spread": 0, "color": "#000000", "inset": true }
+				],
+				"type": "shadow",
+				"name": "shadow.md",
+				"id": "global.shadow.md"
+			})).unwrap());
+
+			assert_eq!(
+				token.value(&MockStore::default(), ReplaceMethod::StaticValues, false, &None),
+				String::from("0px 4px 8px 0px rgb(0, 0, 0), inset 0px 1px 2px 0px rgb(0, 0, 0)")
+			);
+		}
+
+		#[test]
+		fn gradient() {
+			let white = TokenDefinition {
+				id: String::from("global.color.white"),
+				name: String::from("color.white"),
+				value: String::from("#ffffff"),
+				kind: TokenKind::Color,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			};
+
+			let mut tokens = HashMap::new();
+			tokens.insert(white.id.clone(), Token::Standard(white.into()));
+
+			let store = MockStore::new(tokens, HashMap::new(), HashMap::new());
+
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.gradient.fade"),
+				name: String::from("gradient.fade"),
+				value: json!([{ "color": "#000000", "position": 0 }, { "color": "{color.white}", "position": 1 }]),
+				kind: TokenKind::Gradient,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			assert_eq!(
+				token.serialize(&store, ReplaceMethod::CssVariables, &None),
+				String::from("--gradient-fade: linear-gradient(rgb(0, 0, 0) 0%, rgb(var(--color-white)) 100%);")
+			);
+			assert_eq!(
+				token.value(&store, ReplaceMethod::StaticValues, false, &None),
+				String::from("linear-gradient(rgb(0, 0, 0) 0%, rgb(255, 255, 255) 100%)")
+			);
+		}
+
+		#[test]
+		fn object_values_are_not_enriched() {
+			let token = Token::Standard(TokenDefinition {
+				id: String::from("global.sh"),
+				name: String::from("sh"),
+				value: json!({ "x": 0, "y": "{y}" }),
+				kind: TokenKind::Other,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			}.into());
+
+			assert_eq!(token.serialize(&MockStore::default(), ReplaceMethod::CssVariables, &None), String::from("--sh: {\"x\":0,\"y\":\"{y}\"};"));
+			assert_eq!(token.to_json(&MockStore::default(), ReplaceMethod::StaticValues, &None), json!({ "sh": { "x": 0, "y": "{y}" } }));
+		}
+
+		#[test]
+		fn transition() {
+			let duration = TokenDefinition {
+				id: String::from("global.duration.base"),
+				name: String::from("duration.base"),
+				value: json!(150),
+				kind: TokenKind::Duration,
+				description: None,
+				extensions: None,
+				deprecated: None,
+			};
+
+			let mut tokens = HashMap::new();
+			tokens.insert(duration.id.clone(), Token::Standard(duration.into()));
+
+			let store = MockStore::new(tokens, HashMap::new(), HashMap::new());
+
+			let token = Token::Transition(serde_json::from_value(json!({
+				"value": { "duration": "{duration.base} * 2", "delay": "0.1s", "timingFunction": [0.4, 0, 0.2, 1] },
+				"type": "transition",
+				"name": "motion.emphasis",
+				"id": "global.motion.emphasis"
+			})).unwrap());
+
+			assert_eq!(
+				token.serialize(&store, ReplaceMethod::StaticValues, &None),
+				String::from("--motion-emphasis: 300ms cubic-bezier(0.4, 0, 0.2, 1) 0.1s;")
+			);
+			assert_eq!(
+				token.serialize(&store, ReplaceMethod::CssVariables, &None),
+				String::from("--motion-emphasis: calc(var(--duration-base) * 2) cubic-bezier(0.4, 0, 0.2, 1) 0.1s;")
+			);
+			assert_eq!(
+				token.to_json(&store, ReplaceMethod::StaticValues, &None),
+				json!({ "motion": { "emphasis": { "duration": "300ms", "delay": "0.1s", "timingFunction": "cubic-bezier(0.4, 0, 0.2, 1)" } } })
+			);
+		}
+
 		#[test]
 		fn rgb_color_reference() {
 
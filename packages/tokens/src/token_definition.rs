@@ -1,15 +1,18 @@
 use crate::token_kind::TokenKind;
-use crate::shadow_value::{ShadowLayerKind, ShadowValue};
+use crate::border_value::BorderValue;
+use crate::deprecation::{deprecation, Deprecation};
+use crate::shadow_value::ShadowValue;
 use crate::replace_method::ReplaceMethod;
-use crate::regex::REGEX_HB;
 use crate::token_store::TokenStore;
+use crate::token_value::TokenValue;
+use crate::transition_value::TransitionValue;
+use crate::typography_value::TypographyValue;
 
-use colors_transform::{Color, Rgb};
 use serde_derive::{Deserialize, Serialize};
 use serde::de::DeserializeOwned;
 
 /// A TokenDefinition stores the raw data of a token, with a generic property denoting the type of token (Standard, Shadow, Composition, etc.)
-/// Most tokens are expressed as `Standard` tokens - where the value is a simple `String`, although `Shadow` and `Composition` tokens require 
+/// Most tokens are expressed as `Standard` tokens - where the value is a parsed `TokenValue`, although `Shadow` and `Composition` tokens require 
 /// different serialization methods, and therefore we can impl `get_value` for each type. 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(bound(deserialize = "T: DeserializeOwned"))]
@@ -24,68 +27,101 @@ pub struct TokenDefinition<T> {
     pub name: String,
     #[serde(default)]
     pub id: String,
+    /// Notes from the designer on how the token should be used.
+    #[serde(alias = "$description", default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Any extra data attached to the token by Tokens Studio, plugins or other tools, kept as it was written.
+    #[serde(rename = "$extensions", default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<serde_json::Value>,
+    /// Whether the token is deprecated, and the token that replaces it, see `Deprecation`
+    #[serde(alias = "$deprecated", default, deserialize_with = "deprecation", skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecation>,
 }
 
-impl TokenDefinition<String> {
+impl TokenDefinition<TokenValue> {
     pub fn get_value(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, nested: bool, theme: &Option<String>) -> String {
-        let value = if REGEX_HB.is_match(&self.value) {
-            let mut v = store.enrich(self.value.to_string(), replace_method, &theme);
-
-            if self.kind == TokenKind::Color && !v.starts_with("rgb") && !nested {
-                v = format!("rgb({})", v);
-            }
-
-            v
-        } else {
-            if TokenKind::Color == self.kind {
-                Rgb::from_hex_str(&self.value)
-                    .map(|rgb| format!("{}, {}, {}", rgb.get_red(), rgb.get_green(), rgb.get_blue()))
-                    .unwrap_or_else(|_| self.value.clone())  // gracefully handle error
-            } else {
-                self.value.clone()
-            }
-        };
+        let mut value = self.value.with_default_unit(self.kind).resolve(store, replace_method, theme);
+
+        if self.value.has_references() && self.kind == TokenKind::Color && !value.starts_with("rgb") && !nested {
+            value = format!("rgb({})", value);
+        }
 
         value
     }
 }
 
+impl From<TokenDefinition<serde_json::Value>> for TokenDefinition<TokenValue> {
+    /// Parses the value of a token definition as it was written in the original json, see `TokenValue::from_json`
+    fn from(definition: TokenDefinition<serde_json::Value>) -> Self {
+        TokenDefinition {
+            value: TokenValue::from_json(&definition.value, definition.kind),
+            kind: definition.kind,
+            name: definition.name,
+            id: definition.id,
+            description: definition.description,
+            extensions: definition.extensions,
+            deprecated: definition.deprecated,
+        }
+    }
+}
+
+impl From<TokenDefinition<serde_json::Value>> for TokenDefinition<TypographyValue> {
+    /// Reads the fields of a typography token as it was written in the original json, see `TypographyValue::from_json`
+    fn from(definition: TokenDefinition<serde_json::Value>) -> Self {
+        TokenDefinition {
+            value: TypographyValue::from_json(&definition.value),
+            kind: definition.kind,
+            name: definition.name,
+            id: definition.id,
+            description: definition.description,
+            extensions: definition.extensions,
+            deprecated: definition.deprecated,
+        }
+    }
+}
+
+impl From<TokenDefinition<String>> for TokenDefinition<TokenValue> {
+    /// Parses the raw value of a token definition, see `TokenValue::parse`
+    fn from(definition: TokenDefinition<String>) -> Self {
+        TokenDefinition {
+            value: TokenValue::parse(&definition.value, definition.kind),
+            kind: definition.kind,
+            name: definition.name,
+            id: definition.id,
+            description: definition.description,
+            extensions: definition.extensions,
+            deprecated: definition.deprecated,
+        }
+    }
+}
+
 impl TokenDefinition<ShadowValue> {
     pub fn get_value(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
-        // Initialize a new string to hold the value
-        let mut value = String::new();
-
-        // The TokenDefinition for a shadow token value is a Vec<ShadowLayer>, we iterate through these
-        // formatting them as valid CSS shadows, and pushing them into the string.
-        for layer in &self.value.0 {
-            let color = if !layer.color.starts_with("rgb") { format!("rgb({})", layer.color) } else { layer.color.clone() };
-
-            match layer.kind {
-                ShadowLayerKind::DropShadow => value.push_str(&format!(
-                    "{}px {}px {}px {}px {}, ",
-                    layer.x, 
-                    layer.y, 
-                    layer.blur,
-                    layer.spread,
-                    color
-                )),
-                ShadowLayerKind::InnerShadow => value.push_str(&format!(
-                    "inset {}px {}px {}px {}px {}, ",
-                    layer.x, 
-                    layer.y, 
-                    layer.blur,
-                    layer.spread,
-                    color
-                )),
-            };
-        }
+        self.value.resolve(store, replace_method, theme)
+    }
+}
 
-        // Remove the trailing comma and space - we bake in the ", " as a separator between layers of shadows, 
-        // but then must remove the trailing ", " on the final value.
-        value.pop();
-        value.pop();
+impl TokenDefinition<BorderValue> {
+    /// Writes the border as the value of the `border` shorthand, i.e. `1px solid rgb(0, 0, 0)`
+    pub fn get_value(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+        self.value.resolve(store, replace_method, theme)
+    }
 
-        store.enrich(value, replace_method, &theme)
+    /// Writes the border as an object, with each field resolved.
+    pub fn to_json(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> serde_json::Value {
+        self.value.to_json(store, replace_method, theme)
+    }
+}
+
+impl TokenDefinition<TransitionValue> {
+    /// Writes the transition as the value of the `transition` shorthand, i.e. `200ms cubic-bezier(0.4, 0, 0.2, 1) 50ms`
+    pub fn get_value(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+        self.value.resolve(store, replace_method, theme)
+    }
+
+    /// Writes the transition as an object, with each field resolved.
+    pub fn to_json(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> serde_json::Value {
+        self.value.to_json(store, replace_method, theme)
     }
 }
 
@@ -106,5 +142,30 @@ mod tests {
 			assert_eq!(token.name, String::from("fontSize.0"));
 			assert_eq!(token.id, String::from("fontSize.0"));
 		}
+
+		#[test]
+		fn border_width_can_be_a_number() {
+			let token: TokenDefinition<BorderValue> = serde_json::from_str("{\"value\":{\"color\":\"#000000\",\"width\":1,\"style\":\"solid\"},\"type\":\"border\"}").unwrap();
+
+			assert_eq!(token.value.width, String::from("1"));
+			assert_eq!(token.kind, TokenKind::Border);
+		}
+
+		#[test]
+		fn keeps_description_and_extensions() {
+			let token: TokenDefinition<String> = serde_json::from_str("{\"value\":\"8px\",\"type\":\"spacing\",\"description\":\"Gap between cards\",\"$extensions\":{\"studio.tokens\":{\"id\":\"abc\"}}}").unwrap();
+
+			assert_eq!(token.description, Some(String::from("Gap between cards")));
+			assert_eq!(token.extensions, Some(serde_json::json!({ "studio.tokens": { "id": "abc" } })));
+		}
+
+		#[test]
+		fn shadow_can_be_a_single_layer() {
+			let token: TokenDefinition<ShadowValue> = serde_json::from_str("{\"value\":{\"x\":0,\"y\":\"4px\",\"blur\":8,\"spread\":0,\"color\":\"#000000\",\"type\":\"dropShadow\",\"blendMode\":\"multiply\"},\"type\":\"boxShadow\"}").unwrap();
+
+			assert_eq!(token.value.0.len(), 1);
+			assert_eq!(token.value.0[0].x, String::from("0"));
+			assert_eq!(token.value.0[0].blend_mode, Some(String::from("multiply")));
+		}
 	}
 }
@@ -1,5 +1,6 @@
 use serde_derive::Deserialize;
 use serde::{Serialize, Serializer};
+use std::fmt;
 
 /// Each individual token type, usually mapping 1:1 to a css property with the exception of `Composition` and `Dimension`
 /// 
@@ -12,61 +13,126 @@ use serde::{Serialize, Serializer};
 /// Dimension tokens are unique in that they contain a value, as well as the desired "dimension" (i.e. `rem`, `em`, `%`, `px`, `vw`, etc.)
 #[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
 pub enum TokenKind {
+    #[serde(alias = "asset")]
+    Asset,
+    #[serde(alias = "boolean")]
+    Boolean,
+    #[serde(alias = "border")]
+    Border,
     #[serde(alias = "borderRadius")]
     BorderRadius,
     #[serde(alias = "borderWidth")]
     BorderWidth,
-    #[serde(alias = "boxShadow")]
+    #[serde(alias = "boxShadow", alias = "shadow")]
     BoxShadow,
     #[serde(alias = "color")]
     Color,
     #[serde(alias = "composition")]
     Composition,
+    #[serde(alias = "cubicBezier")]
+    CubicBezier,
     #[serde(alias = "dimension")]
     Dimension,
-    #[serde(alias = "fontFamilies")]
+    #[serde(alias = "duration")]
+    Duration,
+    #[serde(alias = "fontFamilies", alias = "fontFamily")]
     FontFamily,
-    #[serde(alias = "fontSizes")]
+    #[serde(alias = "fontSizes", alias = "fontSize")]
     FontSize,
-    #[serde(alias = "fontWeights")]
+    #[serde(alias = "fontWeights", alias = "fontWeight")]
     FontWeights,
+    #[serde(alias = "gradient")]
+    Gradient,
     #[serde(alias = "letterSpacing")]
     LetterSpacing,
-    #[serde(alias = "lineHeights")]
+    #[serde(alias = "lineHeights", alias = "lineHeight")]
     LineHeight,
+    #[serde(alias = "number")]
+    Number,
     #[serde(alias = "opacity")]
     Opacity,
+    #[serde(alias = "paragraphIndent")]
+    ParagraphIndent,
+    #[serde(alias = "paragraphSpacing")]
+    ParagraphSpacing,
     #[serde(alias = "sizing")]
     Sizing,
     #[serde(alias = "spacing")]
     Spacing,
+    #[serde(alias = "strokeStyle")]
+    StrokeStyle,
+    #[serde(alias = "text")]
+    Text,
+    #[serde(alias = "textCase")]
+    TextCase,
+    #[serde(alias = "textDecoration")]
+    TextDecoration,
+    #[serde(alias = "transition")]
+    Transition,
     #[serde(alias = "typography")]
     Typography,
-    #[serde(alias = "other")]
+    /// Any type we don't recognize (i.e. one added in a later version of Tokens Studio) is treated as `Other`, so that
+    /// its value is still passed through.
+    #[serde(alias = "other", other)]
     Other,
 }
-impl ToString for TokenKind {
+impl TokenKind {
+	/// The unit a unitless number is given when it's written as CSS. Tokens Studio writes most dimensions as plain
+	/// numbers of pixels (i.e. `16` for a spacing token), whereas some kinds (i.e. `opacity` or `fontWeights`) are
+	/// unitless in CSS too.
+	pub fn default_unit(&self) -> Option<&'static str> {
+		match self {
+			TokenKind::BorderRadius
+			| TokenKind::BorderWidth
+			| TokenKind::Dimension
+			| TokenKind::FontSize
+			| TokenKind::LetterSpacing
+			| TokenKind::ParagraphIndent
+			| TokenKind::ParagraphSpacing
+			| TokenKind::Sizing
+			| TokenKind::Spacing => Some("px"),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for TokenKind {
 	/// Handles the mapping of Self to css property name for use in serialization.
-    fn to_string(&self) -> String {
-        match self {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let property = match self {
+            TokenKind::Asset => "asset",
+            TokenKind::Boolean => "boolean",
+            TokenKind::Border => "border",
             TokenKind::BorderRadius => "border-radius",
             TokenKind::BorderWidth => "border-width",
             TokenKind::BoxShadow => "box-shadow",
             TokenKind::Color => "color",
             TokenKind::Composition => "composition",
+            TokenKind::CubicBezier => "transition-timing-function",
             TokenKind::Dimension => "dimension",
+            TokenKind::Duration => "transition-duration",
             TokenKind::FontFamily => "font-family",
             TokenKind::FontSize => "font-size",
             TokenKind::FontWeights => "font-weight",
+            TokenKind::Gradient => "background-image",
             TokenKind::LetterSpacing => "letter-spacing",
             TokenKind::LineHeight => "line-height",
+            TokenKind::Number => "number",
             TokenKind::Opacity => "opacity",
+            TokenKind::ParagraphIndent => "text-indent",
+            TokenKind::ParagraphSpacing => "margin-block-end",
             TokenKind::Sizing => "sizing",
             TokenKind::Spacing => "spacing",
+            TokenKind::StrokeStyle => "border-style",
+            TokenKind::Text => "content",
+            TokenKind::TextCase => "text-transform",
+            TokenKind::TextDecoration => "text-decoration",
+            TokenKind::Transition => "transition",
             TokenKind::Typography => "typography",
             TokenKind::Other => "other",
-        }
-        .to_string()
+        };
+
+        write!(f, "{}", property)
     }
 }
 impl Serialize for TokenKind {
@@ -76,4 +142,31 @@ impl Serialize for TokenKind {
 	{
 		serializer.serialize_str(&self.to_string())
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use test_case::test_case;
+
+	#[test_case(TokenKind::Spacing, Some("px") ; "spacing")]
+	#[test_case(TokenKind::FontSize, Some("px") ; "font size")]
+	#[test_case(TokenKind::Opacity, None ; "opacity")]
+	#[test_case(TokenKind::FontWeights, None ; "font weight")]
+	#[test_case(TokenKind::LineHeight, None ; "line height")]
+	fn default_units(kind: TokenKind, expected: Option<&str>) {
+		assert_eq!(kind.default_unit(), expected);
+	}
+
+	#[test_case("fontFamilies", TokenKind::FontFamily ; "tokens studio")]
+	#[test_case("fontFamily", TokenKind::FontFamily ; "dtcg")]
+	#[test_case("cubicBezier", TokenKind::CubicBezier ; "cubic bezier")]
+	#[test_case("paragraphSpacing", TokenKind::ParagraphSpacing ; "paragraph spacing")]
+	#[test_case("shadow", TokenKind::BoxShadow ; "dtcg shadow")]
+	#[test_case("gradient", TokenKind::Gradient ; "gradient")]
+	#[test_case("other", TokenKind::Other ; "other")]
+	#[test_case("somethingNew", TokenKind::Other ; "unknown type")]
+	fn deserializes(input: &str, expected: TokenKind) {
+		assert_eq!(serde_json::from_value::<TokenKind>(serde_json::json!(input)).unwrap(), expected);
+	}
+}
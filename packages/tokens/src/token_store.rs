@@ -1,4 +1,5 @@
 use crate::token::Token;
+use crate::naming::Naming;
 use crate::replace_method::ReplaceMethod;
 
 /// `TokenStore` is a trait that defines a storage interface for managing, retrieving,
@@ -13,7 +14,7 @@ pub trait TokenStore {
     /// # Returns
     ///
     /// * A reference to the `Token` object associated with the provided ID.
-    fn token(&self, id: &String) -> &Token;
+    fn token(&self, id: &str) -> &Token;
 
     /// Returns a list of all tokens in the store. Optionally, filters the list by theme.
     ///
@@ -47,6 +48,21 @@ pub trait TokenStore {
         replace_method: ReplaceMethod,
         theme: &Option<String>,
     ) -> String;
+
+    /// Returns how the names of tokens are written in the output, used wherever a token is written or referenced
+    /// by name so that the two stay consistent.
+    ///
+    /// # Returns
+    ///
+    /// * A reference to the `Naming` of the store.
+    fn naming(&self) -> &Naming;
+
+    /// Returns the size of a rem (in pixels), used wherever a value in rem has to be compared to one in pixels.
+    ///
+    /// # Returns
+    ///
+    /// * The base font size of the store, in pixels.
+    fn base_font_size(&self) -> f64;
 }
 
 #[cfg(test)]
@@ -55,15 +71,21 @@ pub mod test_utils {
 
 	use super::TokenStore;
 	use crate::regex::REGEX_HB;
-	use crate::utils::css_stringify;
-	use crate::{ReplaceMethod, Tokens, TokenSets, Themes};
+	use crate::{Naming, ReplaceMethod, Tokens, TokenSets, Themes};
 	use crate::Token;
 
-	#[derive(Default)]
 	pub struct MockStore {
 		pub tokens: Tokens,
 		pub token_sets: TokenSets,
-		pub themes: Themes
+		pub themes: Themes,
+		pub naming: Naming,
+		pub base_font_size: f64,
+	}
+
+	impl Default for MockStore {
+		fn default() -> Self {
+			Self::new(Tokens::new(), TokenSets::new(), Themes::new())
+		}
 	}
 
 	impl MockStore {
@@ -71,13 +93,15 @@ pub mod test_utils {
 			Self { 
 				tokens,
 				token_sets,
-				themes
+				themes,
+				naming: Naming::default(),
+				base_font_size: 16.0,
 			}
 		}
 	}
 
 	impl TokenStore for MockStore {
-		fn token(&self, id: &String) -> &Token {
+		fn token(&self, id: &str) -> &Token {
 			&self.tokens[id]
 		}
 
@@ -85,9 +109,9 @@ pub mod test_utils {
 			if let Some(key) = theme {
 				// If the theme arg is provided, get the theme to check which sets should be active, and then filter to return only these tokens.
 				let active_sets = self.themes.get(key).unwrap();
-				active_sets.keys().map(|set_name| &self.token_sets[set_name]).flatten().map(|token_id| &self.tokens[token_id]).collect()
+				active_sets.keys().flat_map(|set_name| &self.token_sets[set_name]).map(|token_id| &self.tokens[token_id]).collect()
 			} else {
-				self.tokens.values().map(|t| t).collect::<Vec<&Token>>()
+				self.tokens.values().collect::<Vec<&Token>>()
 			}
 		}
 
@@ -97,7 +121,12 @@ pub mod test_utils {
 					let name = &caps[1];
 
 					match replace_method {
-						ReplaceMethod::CssVariables => format!("var(--{})", css_stringify(&name.to_string())),
+						ReplaceMethod::CssVariables => match self.tokens(theme).iter().find(|t| t.name() == name) {
+							// Private tokens aren't output as variables, so their value is inlined instead.
+							Some(t) if t.is_private() => t.value(self, replace_method, true, theme),
+							Some(t) => format!("var(--{})", t.css_name(self)),
+							None => format!("var(--{})", self.naming.css_name(name, None)),
+						},
 						ReplaceMethod::StaticValues => {
 							if let Some(t) = self.tokens(theme).iter().find(|t| t.name() == name) {
 								t.value(self, replace_method, true, theme)
@@ -109,5 +138,13 @@ pub mod test_utils {
 				})
 				.to_string()
 		}
+
+		fn naming(&self) -> &Naming {
+			&self.naming
+		}
+
+		fn base_font_size(&self) -> f64 {
+			self.base_font_size
+		}
 	}
 }
\ No newline at end of file
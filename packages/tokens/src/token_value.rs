@@ -0,0 +1,481 @@
+use std::fmt;
+
+use colors_transform::{Color, Rgb};
+use serde_json::json;
+
+use crate::fluid_value::FluidValue;
+use crate::regex::REGEX_HB;
+use crate::replace_method::ReplaceMethod;
+use crate::token_kind::TokenKind;
+use crate::token_store::TokenStore;
+use crate::utils::references;
+
+/// The value of a `Standard` token, parsed once when the token is loaded so that each output format can render it
+/// natively rather than re-parsing the original string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenValue {
+    /// A number with a unit, i.e. `16px`, `1.5rem` or `150%`
+    Dimension(f64, String),
+    /// A plain number, i.e. `400` or `0.5`
+    Number(f64),
+    /// A hex color, i.e. `#7f56d9`
+    Color(Rgb),
+    /// A boolean, i.e. `true`
+    Boolean(bool),
+    /// A cubic bézier easing curve, i.e. `[0.4, 0, 0.2, 1]`
+    CubicBezier([f64; 4]),
+    /// The url of an asset, i.e. `https://example.com/logo.svg`
+    Url(String),
+    /// A string of text, i.e. `Hello World`
+    Text(String),
+    /// A list of font families, i.e. `Inter, sans-serif` or `["Inter", "sans-serif"]`
+    FontFamilies(Vec<String>),
+    /// Any other list of values, i.e. `["a", "b"]`
+    List(Vec<String>),
+    /// A value that is nothing but a reference to another token, i.e. `{color.purple.1}`. Holds the name of the
+    /// referenced token.
+    Reference(String),
+    /// A math statement, which may reference other tokens, i.e. `{spacing.base} * 2`
+    Math(String),
+    /// A dimension that scales with the viewport, written as `clamp()`, see `FluidValue`
+    Fluid(Box<FluidValue>),
+    /// A linear gradient, written in the DTCG format as a list of color stops with a position between 0 and 1, i.e.
+    /// `[{ "color": "#000000", "position": 0 }, { "color": "{color.white}", "position": 1 }]`
+    Gradient(Vec<(TokenValue, f64)>),
+    /// A json value that has no CSS equivalent (i.e. an object), kept as it was written. It's never enriched, as any
+    /// braces in it belong to the json rather than a reference.
+    Json(serde_json::Value),
+    /// Anything else is passed through as it was written, i.e. `none` or `rgba({color.black}, 0.5)`
+    Raw(String),
+}
+
+impl TokenValue {
+    /// Parses the value of a token, using its kind to decide how ambiguous values should be read (i.e. a comma separated
+    /// list is only a list of font families for `fontFamilies` tokens.)
+    pub fn parse(value: &str, kind: TokenKind) -> Self {
+        let value = value.trim();
+
+        if let Some(name) = reference(value) {
+            return TokenValue::Reference(name.to_string());
+        }
+
+        // Text and assets are taken as they are, even if they happen to look like a number or math statement.
+        match kind {
+            TokenKind::Text => return TokenValue::Text(value.to_string()),
+            TokenKind::Asset => return TokenValue::Url(value.to_string()),
+            TokenKind::Boolean if value == "true" || value == "false" => return TokenValue::Boolean(value == "true"),
+            _ => {}
+        }
+
+        // References are substituted for a variable, so the rest of the statement can be checked by our css math parser.
+        if css_math::is_css_math(&REGEX_HB.replace_all(value, "var(--reference)")) {
+            return TokenValue::Math(value.to_string());
+        }
+
+        if REGEX_HB.is_match(value) {
+            return TokenValue::Raw(value.to_string());
+        }
+
+        if let Some(number) = number(value) {
+            return match kind {
+                // Durations are written in milliseconds when no unit is given.
+                TokenKind::Duration => TokenValue::Dimension(number, String::from("ms")),
+                _ => TokenValue::Number(number),
+            };
+        }
+
+        if let Some((number, unit)) = dimension(value) {
+            return TokenValue::Dimension(number, unit.to_string());
+        }
+
+        match kind {
+            TokenKind::CubicBezier => match cubic_bezier(value) {
+                Some(points) => TokenValue::CubicBezier(points),
+                None => TokenValue::Raw(value.to_string()),
+            },
+            TokenKind::Color => match Rgb::from_hex_str(value) {
+                Ok(rgb) => TokenValue::Color(rgb),
+                Err(_) => TokenValue::Raw(value.to_string()),
+            },
+            TokenKind::FontFamily => TokenValue::FontFamilies(
+                value
+                    .split(',')
+                    .map(|family| family.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+                    .filter(|family| !family.is_empty())
+                    .collect(),
+            ),
+            _ => TokenValue::Raw(value.to_string()),
+        }
+    }
+
+    /// Parses a value from the original json, Tokens Studio may write numbers (i.e. `16` or `400`), booleans and
+    /// arrays (i.e. `["Inter", "sans-serif"]` for `fontFamilies`) as well as strings.
+    pub fn from_json(value: &serde_json::Value, kind: TokenKind) -> Self {
+        match value {
+            serde_json::Value::String(s) => TokenValue::parse(s, kind),
+            serde_json::Value::Number(n) => TokenValue::parse(&n.to_string(), kind),
+            serde_json::Value::Bool(b) => TokenValue::Boolean(*b),
+            serde_json::Value::Array(items) if kind == TokenKind::CubicBezier => {
+                let points = items.iter().filter_map(|item| item.as_f64()).collect::<Vec<f64>>();
+
+                match points.try_into() {
+                    Ok(points) => TokenValue::CubicBezier(points),
+                    Err(_) => TokenValue::Raw(value.to_string()),
+                }
+            }
+            serde_json::Value::Array(stops) if kind == TokenKind::Gradient => stops
+                .iter()
+                .map(|stop| Some((TokenValue::from_json(stop.get("color")?, TokenKind::Color), stop.get("position")?.as_f64()?)))
+                .collect::<Option<Vec<(TokenValue, f64)>>>()
+                .map_or_else(|| TokenValue::Json(value.clone()), TokenValue::Gradient),
+            serde_json::Value::Array(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| match item {
+                        serde_json::Value::String(s) => s.trim().to_string(),
+                        item => item.to_string(),
+                    })
+                    .collect();
+
+                match kind {
+                    TokenKind::FontFamily => TokenValue::FontFamilies(items),
+                    _ => TokenValue::List(items),
+                }
+            }
+            // Objects (and null) aren't valid standard token values, we pass them through rather than failing the build.
+            value => TokenValue::Json(value.clone()),
+        }
+    }
+
+    /// Gives a unitless number the default unit of the kind of token it belongs to, see `TokenKind::default_unit`. This
+    /// is applied when the value is written as CSS, so JSON output can keep the number as it was written. Static math
+    /// that folds to a unitless number (i.e. `4 * 2` for a spacing token) is given the unit too.
+    pub fn with_default_unit(&self, kind: TokenKind) -> Self {
+        match (self, kind.default_unit()) {
+            (TokenValue::Number(n), Some(unit)) => TokenValue::Dimension(*n, unit.to_string()),
+            (TokenValue::Math(value), Some(unit)) if !self.has_references() => match css_math::evaluate(value) {
+                // Written as the folded value would have been, i.e. `3.3333px` for `10 / 3`
+                Some(css_math::Value { number, unit: None }) => TokenValue::Math(css_math::Value::new(number, Some(unit.to_string())).to_string()),
+                _ => self.clone(),
+            },
+            (value, _) => value.clone(),
+        }
+    }
+
+    /// The names of the tokens the value references.
+    pub fn references(&self) -> Vec<String> {
+        match self {
+            TokenValue::Reference(name) => vec![name.clone()],
+            TokenValue::Math(value) | TokenValue::Raw(value) => references(value),
+            TokenValue::FontFamilies(items) | TokenValue::List(items) => items.iter().flat_map(|item| references(item)).collect(),
+            TokenValue::Fluid(fluid) => [&fluid.min, &fluid.max].into_iter().flat_map(|value| value.references()).collect(),
+            TokenValue::Gradient(stops) => stops.iter().flat_map(|(color, _)| color.references()).collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Whether the value contains references to other tokens that need to be enriched against the store.
+    pub fn has_references(&self) -> bool {
+        match self {
+            TokenValue::Reference(_) => true,
+            TokenValue::Math(value) | TokenValue::Raw(value) => REGEX_HB.is_match(value),
+            TokenValue::FontFamilies(items) | TokenValue::List(items) => items.iter().any(|item| REGEX_HB.is_match(item)),
+            TokenValue::Fluid(fluid) => fluid.min.has_references() || fluid.max.has_references(),
+            TokenValue::Gradient(stops) => stops.iter().any(|(color, _)| color.has_references()),
+            _ => false,
+        }
+    }
+
+    /// Renders the value as CSS, enriching any references against the store. Math statements are run through our css
+    /// math formatter, anything that can be computed ahead of time is folded into a single value, and whatever is left
+    /// for the browser to resolve (i.e. mixed units, or references to css variables) is printed as the shortest valid
+    /// css, only wrapped in calc() when needed.
+    pub fn resolve(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+        match self {
+            TokenValue::Fluid(fluid) => return fluid.resolve(store, replace_method, theme),
+            // Each color is resolved on its own, so references to color tokens are written as complete colors.
+            TokenValue::Gradient(stops) => {
+                return linear_gradient(stops.iter().map(|(color, position)| (color.resolve_color(store, replace_method, theme), *position)))
+            }
+            _ => {}
+        }
+
+        let value = if self.has_references() {
+            store.enrich(self.to_string(), replace_method, theme)
+        } else {
+            self.to_string()
+        };
+
+        match self {
+            TokenValue::Math(_) => css_math::format(&value).unwrap_or(value),
+            _ => value,
+        }
+    }
+
+    /// Renders the value as a complete CSS color. Standard color tokens are written as a list of channels, so hex colors
+    /// and references to other color tokens are wrapped in `rgb()`
+    pub fn resolve_color(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+        let value = self.resolve(store, replace_method, theme);
+
+        match self {
+            TokenValue::Color(_) => format!("rgb({})", value),
+            _ if self.has_references() && !value.starts_with("rgb") => format!("rgb({})", value),
+            _ => value,
+        }
+    }
+
+    /// Renders the value as JSON, numbers and booleans keep their types and lists are written as arrays.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            // Whole numbers are written without a decimal point, i.e. `400` rather than `400.0`
+            TokenValue::Number(n) if n.fract() == 0.0 && n.abs() < i64::MAX as f64 => json!(*n as i64),
+            TokenValue::Number(n) => json!(n),
+            TokenValue::Boolean(b) => json!(b),
+            TokenValue::CubicBezier(points) => json!(points),
+            TokenValue::Url(value) | TokenValue::Text(value) => json!(value),
+            // A single font family is written as a string, the same way it would have been written in the original json.
+            TokenValue::FontFamilies(items) if items.len() == 1 => json!(items[0]),
+            TokenValue::FontFamilies(items) | TokenValue::List(items) => json!(items),
+            // Static math is evaluated (i.e. `8px * 2` is written as `16px`), anything left for the browser to compute is
+            // written as css, the same way it would be in a stylesheet.
+            TokenValue::Json(value) => value.clone(),
+            TokenValue::Math(value) => match css_math::evaluate(value) {
+                Some(css_math::Value { number, unit: None }) => TokenValue::Number(number).to_json(),
+                Some(result) => json!(result.to_string()),
+                None => json!(css_math::format(value).unwrap_or_else(|_| value.clone())),
+            },
+            _ => json!(self.to_string()),
+        }
+    }
+}
+
+/// The name of the referenced token, if the value is a single reference i.e. `{color.purple.1}`
+fn reference(value: &str) -> Option<&str> {
+    let name = value.strip_prefix('{')?.strip_suffix('}')?;
+
+    if name.is_empty() || name.contains(['{', '}']) {
+        return None;
+    }
+
+    Some(name)
+}
+
+/// Parses a plain number, rejecting anything f64 would accept that isn't a CSS number (i.e. `inf` or `NaN`)
+fn number(value: &str) -> Option<f64> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+')) {
+        return None;
+    }
+
+    value.parse::<f64>().ok()
+}
+
+/// Splits a dimension into its number and unit, the unit must be one CSS recognizes.
+fn dimension(value: &str) -> Option<(f64, &str)> {
+    let index = value.find(|c: char| c.is_ascii_alphabetic() || c == '%')?;
+    let (n, unit) = value.split_at(index);
+
+    css_math::Type::from_unit(unit)?;
+
+    Some((number(n)?, unit))
+}
+
+/// Parses the points of a cubic bézier curve written as a list, i.e. `0.4, 0, 0.2, 1` or `cubic-bezier(0.4, 0, 0.2, 1)`
+fn cubic_bezier(value: &str) -> Option<[f64; 4]> {
+    let points = value.strip_prefix("cubic-bezier(").and_then(|v| v.strip_suffix(')')).unwrap_or(value);
+
+    points
+        .split(',')
+        .map(|point| number(point.trim()))
+        .collect::<Option<Vec<f64>>>()?
+        .try_into()
+        .ok()
+}
+
+/// Writes the color stops of a gradient as `linear-gradient()`, positions are given as a fraction of its length.
+fn linear_gradient(stops: impl Iterator<Item = (String, f64)>) -> String {
+    let stops = stops
+        .map(|(color, position)| format!("{} {}", color, css_math::Value::new(position * 100.0, Some(String::from("%")))))
+        .collect::<Vec<String>>();
+
+    format!("linear-gradient({})", stops.join(", "))
+}
+
+/// Quotes a font family name if it can't be written as a CSS identifier, i.e. `"Open Sans"`. References to other
+/// font families are left as they are.
+fn font_family(family: &str) -> String {
+    let identifier = family.chars().next().is_some_and(|c| c.is_alphabetic() || c == '-')
+        && family.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        || REGEX_HB.is_match(family);
+
+    if identifier {
+        family.to_string()
+    } else {
+        format!("\"{}\"", family.replace('"', "\\\""))
+    }
+}
+
+impl fmt::Display for TokenValue {
+    /// Prints the value as CSS, references are printed using the handlebars syntax they were written in so they can be
+    /// enriched against the store.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenValue::Dimension(n, unit) => write!(f, "{}{}", n, unit),
+            TokenValue::Number(n) => write!(f, "{}", n),
+            TokenValue::Boolean(b) => write!(f, "{}", b),
+            TokenValue::CubicBezier([x1, y1, x2, y2]) => write!(f, "cubic-bezier({}, {}, {}, {})", x1, y1, x2, y2),
+            TokenValue::Url(url) => write!(f, "url(\"{}\")", url.replace('"', "\\\"")),
+            TokenValue::Text(text) => write!(f, "\"{}\"", text.replace('"', "\\\"")),
+            // Colors are written as a list of channels, so they can be used with an alpha value i.e. `rgba(var(--purple), 0.5)`
+            TokenValue::Color(rgb) => write!(f, "{}, {}, {}", rgb.get_red(), rgb.get_green(), rgb.get_blue()),
+            TokenValue::FontFamilies(families) => write!(
+                f,
+                "{}",
+                families.iter().map(|family| font_family(family)).collect::<Vec<String>>().join(", ")
+            ),
+            TokenValue::List(items) => write!(f, "{}", items.join(", ")),
+            TokenValue::Reference(name) => write!(f, "{{{}}}", name),
+            TokenValue::Math(value) | TokenValue::Raw(value) => write!(f, "{}", value),
+            TokenValue::Fluid(fluid) => write!(f, "{}", fluid),
+            TokenValue::Gradient(stops) => {
+                let stops = stops.iter().map(|(color, position)| match color {
+                    TokenValue::Color(_) => (format!("rgb({})", color), *position),
+                    _ => (color.to_string(), *position),
+                });
+
+                write!(f, "{}", linear_gradient(stops))
+            }
+            TokenValue::Json(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token_store::test_utils::MockStore;
+    use matches::assert_matches;
+    use test_case::test_case;
+
+    #[test_case("16px", 16.0, "px" ; "px")]
+    #[test_case("1.5rem", 1.5, "rem" ; "rem")]
+    #[test_case("150%", 150.0, "%" ; "percentage")]
+    #[test_case("-2px", -2.0, "px" ; "negative")]
+    fn parses_dimension(input: &str, number: f64, unit: &str) {
+        assert_eq!(TokenValue::parse(input, TokenKind::Spacing), TokenValue::Dimension(number, unit.to_string()));
+    }
+
+    #[test_case("400", 400.0 ; "integer")]
+    #[test_case("0.5", 0.5 ; "decimal")]
+    fn parses_number(input: &str, number: f64) {
+        assert_eq!(TokenValue::parse(input, TokenKind::Opacity), TokenValue::Number(number));
+    }
+
+    #[test]
+    fn parses_color() {
+        assert_matches!(TokenValue::parse("#7f56d9", TokenKind::Color), TokenValue::Color(_));
+        assert_eq!(TokenValue::parse("#7f56d9", TokenKind::Color).to_string(), "127, 86, 217");
+    }
+
+    #[test_case("Inter", "Inter" ; "single family")]
+    #[test_case("Open Sans, sans-serif", "\"Open Sans\", sans-serif" ; "quotes families with spaces")]
+    #[test_case("'Open Sans', Arial", "\"Open Sans\", Arial" ; "normalises quotes")]
+    fn parses_font_families(input: &str, expected: &str) {
+        let value = TokenValue::parse(input, TokenKind::FontFamily);
+
+        assert_matches!(value, TokenValue::FontFamilies(_));
+        assert_eq!(value.to_string(), expected);
+    }
+
+    #[test]
+    fn parses_reference() {
+        assert_eq!(
+            TokenValue::parse("{color.purple.1}", TokenKind::Color),
+            TokenValue::Reference(String::from("color.purple.1"))
+        );
+    }
+
+    #[test_case("{spacing.base} * 2" ; "reference")]
+    #[test_case("{spacing.base}*{scale}" ; "references without whitespace")]
+    #[test_case("100% - 16px" ; "without references")]
+    fn parses_math(input: &str) {
+        assert_matches!(TokenValue::parse(input, TokenKind::Spacing), TokenValue::Math(_));
+    }
+
+    #[test_case("none" ; "keyword")]
+    #[test_case("rgba({color.black}, 0.5)" ; "reference within a value")]
+    #[test_case("{a} {b}" ; "multiple references")]
+    #[test_case("16pixels" ; "unknown unit")]
+    #[test_case("inf" ; "not a css number")]
+    fn parses_raw(input: &str) {
+        assert_matches!(TokenValue::parse(input, TokenKind::Other), TokenValue::Raw(_));
+    }
+
+    #[test_case(json!(16), TokenKind::Spacing, TokenValue::Number(16.0) ; "integer")]
+    #[test_case(json!(0.5), TokenKind::Opacity, TokenValue::Number(0.5) ; "decimal")]
+    #[test_case(json!(true), TokenKind::Other, TokenValue::Boolean(true) ; "boolean")]
+    #[test_case(json!("16px"), TokenKind::Spacing, TokenValue::Dimension(16.0, String::from("px")) ; "string")]
+    #[test_case(
+        json!(["Open Sans", "sans-serif"]),
+        TokenKind::FontFamily,
+        TokenValue::FontFamilies(vec![String::from("Open Sans"), String::from("sans-serif")]) ;
+        "font families"
+    )]
+    #[test_case(json!(["a", "b"]), TokenKind::Other, TokenValue::List(vec![String::from("a"), String::from("b")]) ; "list")]
+    fn from_json(input: serde_json::Value, kind: TokenKind, expected: TokenValue) {
+        assert_eq!(TokenValue::from_json(&input, kind), expected);
+    }
+
+    #[test_case(json!(["Open Sans", "sans-serif"]), "\"Open Sans\", sans-serif" ; "quoted font stack")]
+    #[test_case(json!(["{font.brand}", "serif"]), "{font.brand}, serif" ; "font stack with a reference")]
+    #[test_case(json!(400), "400" ; "number")]
+    #[test_case(json!(false), "false" ; "boolean")]
+    fn renders_json_values(input: serde_json::Value, expected: &str) {
+        assert_eq!(TokenValue::from_json(&input, TokenKind::FontFamily).to_string(), expected);
+    }
+
+    #[test_case(json!([0.4, 0, 0.2, 1]), TokenKind::CubicBezier, "cubic-bezier(0.4, 0, 0.2, 1)" ; "cubic bezier")]
+    #[test_case(json!("https://example.com/logo.svg"), TokenKind::Asset, "url(\"https://example.com/logo.svg\")" ; "asset")]
+    #[test_case(json!("Say \"hi\""), TokenKind::Text, "\"Say \\\"hi\\\"\"" ; "text")]
+    #[test_case(json!("1 + 2"), TokenKind::Text, "\"1 + 2\"" ; "text that looks like math")]
+    #[test_case(json!("true"), TokenKind::Boolean, "true" ; "boolean string")]
+    #[test_case(json!([{ "color": "#000000", "position": 0 }, { "color": "rgba(0, 0, 0, 0.5)", "position": 0.5 }]), TokenKind::Gradient, "linear-gradient(rgb(0, 0, 0) 0%, rgba(0, 0, 0, 0.5) 50%)" ; "gradient")]
+    #[test_case(json!({ "offsetX": "{x}" }), TokenKind::Other, "{\"offsetX\":\"{x}\"}" ; "object")]
+    #[test_case(json!(150), TokenKind::Duration, "150ms" ; "unitless duration")]
+    #[test_case(json!("0.2s"), TokenKind::Duration, "0.2s" ; "duration")]
+    #[test_case(json!("0.4, 0, 0.2, 1"), TokenKind::CubicBezier, "cubic-bezier(0.4, 0, 0.2, 1)" ; "cubic bezier list")]
+    #[test_case(json!("ease-in-out"), TokenKind::CubicBezier, "ease-in-out" ; "easing keyword")]
+    fn renders_other_kinds(input: serde_json::Value, kind: TokenKind, expected: &str) {
+        assert_eq!(TokenValue::from_json(&input, kind).to_string(), expected);
+    }
+
+    #[test_case("16", TokenKind::Spacing, "16px" ; "number")]
+    #[test_case("4 * 2", TokenKind::Spacing, "8px" ; "spacing math")]
+    #[test_case("10 / 3", TokenKind::FontSize, "3.3333px" ; "rounded math")]
+    #[test_case("4px * 2", TokenKind::Spacing, "8px" ; "math with a unit")]
+    #[test_case("4 * 2", TokenKind::Number, "8" ; "unitless kind")]
+    fn resolves_with_default_unit(input: &str, kind: TokenKind, expected: &str) {
+        let value = TokenValue::parse(input, kind).with_default_unit(kind);
+
+        assert_eq!(value.resolve(&MockStore::default(), ReplaceMethod::StaticValues, &None), expected);
+    }
+
+    #[test]
+    fn to_json() {
+        assert_eq!(TokenValue::parse("400", TokenKind::FontWeights).to_json(), json!(400));
+        assert_eq!(TokenValue::parse("0.5", TokenKind::Opacity).to_json(), json!(0.5));
+        assert_eq!(TokenValue::parse("Inter, serif", TokenKind::FontFamily).to_json(), json!(["Inter", "serif"]));
+        assert_eq!(TokenValue::parse("16px", TokenKind::Spacing).to_json(), json!("16px"));
+        assert_eq!(TokenValue::from_json(&json!(true), TokenKind::Other).to_json(), json!(true));
+        assert_eq!(TokenValue::parse("https://example.com/a.png", TokenKind::Asset).to_json(), json!("https://example.com/a.png"));
+        assert_eq!(TokenValue::from_json(&json!([0.4, 0, 0.2, 1]), TokenKind::CubicBezier).to_json(), json!([0.4, 0.0, 0.2, 1.0]));
+        assert_eq!(TokenValue::from_json(&json!({ "a": "{b}" }), TokenKind::Other).to_json(), json!({ "a": "{b}" }));
+    }
+
+    #[test_case("8px * 2", TokenKind::Spacing, json!("16px") ; "dimension")]
+    #[test_case("1.25 ^ 2", TokenKind::Number, json!(1.5625) ; "number")]
+    #[test_case("4 * 100", TokenKind::FontWeights, json!(400) ; "whole number")]
+    #[test_case("100% - 16px", TokenKind::Sizing, json!("calc(100% - 16px)") ; "computed by the browser")]
+    fn math_to_json(value: &str, kind: TokenKind, expected: serde_json::Value) {
+        assert_eq!(TokenValue::parse(value, kind).to_json(), expected);
+    }
+}
@@ -0,0 +1,63 @@
+use serde_derive::{Serialize, Deserialize};
+
+use crate::replace_method::ReplaceMethod;
+use crate::token_kind::TokenKind;
+use crate::token_store::TokenStore;
+use crate::token_value::TokenValue;
+use crate::utils::json_references;
+
+/// Transition tokens (from the DTCG format) are Objects with a predictable schema, similarly to shadow tokens. Each
+/// field may hold a static value or a reference to another token, and the timing function may be written as a list
+/// of the points of a cubic bézier curve, so fields are kept as json until they're resolved.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionValue {
+    pub(crate) duration: serde_json::Value,
+    #[serde(default)]
+    pub(crate) delay: Option<serde_json::Value>,
+    pub(crate) timing_function: serde_json::Value,
+}
+
+impl TransitionValue {
+    /// The names of the tokens referenced by any of the fields.
+    pub fn references(&self) -> Vec<String> {
+        [Some(&self.duration), self.delay.as_ref(), Some(&self.timing_function)]
+            .into_iter()
+            .flatten()
+            .flat_map(json_references)
+            .collect()
+    }
+
+    /// Writes the transition as the value of the `transition` shorthand, i.e. `200ms cubic-bezier(0.4, 0, 0.2, 1) 50ms`
+    pub fn resolve(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> String {
+        let (duration, timing_function, delay) = self.fields(store, replace_method, theme);
+
+        match delay {
+            Some(delay) => format!("{} {} {}", duration, timing_function, delay),
+            None => format!("{} {}", duration, timing_function),
+        }
+    }
+
+    /// Writes the transition as an object, with each field resolved.
+    pub fn to_json(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> serde_json::Value {
+        let (duration, timing_function, delay) = self.fields(store, replace_method, theme);
+
+        let mut j = serde_json::json!({ "duration": duration, "timingFunction": timing_function });
+        if let Some(delay) = delay {
+            j["delay"] = serde_json::json!(delay);
+        }
+
+        j
+    }
+
+    /// Resolves the duration, timing function and (optional) delay of the transition.
+    fn fields(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> (String, String, Option<String>) {
+        let resolve = |value: &serde_json::Value, kind: TokenKind| TokenValue::from_json(value, kind).resolve(store, replace_method, theme);
+
+        (
+            resolve(&self.duration, TokenKind::Duration),
+            resolve(&self.timing_function, TokenKind::CubicBezier),
+            self.delay.as_ref().map(|delay| resolve(delay, TokenKind::Duration)),
+        )
+    }
+}
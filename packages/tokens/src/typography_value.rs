@@ -0,0 +1,300 @@
+use serde_json::json;
+
+use crate::replace_method::ReplaceMethod;
+use crate::token_kind::TokenKind;
+use crate::token_store::TokenStore;
+use crate::token_value::TokenValue;
+
+/// Figma Token Studio provides Typography token values as Objects, similarly to a composition token. Unlike a
+/// composition token though the keys are a fixed set of Tokens Studio fields, some of which don't share a name
+/// (or even a value format) with the CSS property they map to, i.e. `paragraphSpacing` or `textCase`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypographyValue {
+    pub(crate) font_family: Option<TokenValue>,
+    pub(crate) font_weight: Option<TokenValue>,
+    pub(crate) font_size: Option<TokenValue>,
+    pub(crate) line_height: Option<TokenValue>,
+    pub(crate) letter_spacing: Option<TokenValue>,
+    pub(crate) paragraph_spacing: Option<TokenValue>,
+    pub(crate) paragraph_indent: Option<TokenValue>,
+    pub(crate) text_case: Option<TokenValue>,
+    pub(crate) text_decoration: Option<TokenValue>,
+}
+
+/// Maps the weight names Figma uses for font styles to their numeric CSS value.
+const FONT_WEIGHTS: [(&str, &str); 12] = [
+    ("thin", "100"),
+    ("hairline", "100"),
+    ("extralight", "200"),
+    ("ultralight", "200"),
+    ("light", "300"),
+    ("regular", "400"),
+    ("normal", "400"),
+    ("medium", "500"),
+    ("semibold", "600"),
+    ("bold", "700"),
+    ("extrabold", "800"),
+    ("black", "900"),
+];
+
+impl TypographyValue {
+    /// Reads each field of the typography token from the original json, parsing it as the kind of token it would be
+    /// if it were defined on its own.
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        let field = |key: &str, kind: TokenKind| value.get(key).map(|v| TokenValue::from_json(v, kind));
+
+        TypographyValue {
+            font_family: field("fontFamily", TokenKind::FontFamily),
+            font_weight: field("fontWeight", TokenKind::FontWeights),
+            font_size: field("fontSize", TokenKind::FontSize),
+            line_height: field("lineHeight", TokenKind::LineHeight),
+            letter_spacing: field("letterSpacing", TokenKind::LetterSpacing),
+            paragraph_spacing: field("paragraphSpacing", TokenKind::Dimension),
+            paragraph_indent: field("paragraphIndent", TokenKind::Dimension),
+            text_case: field("textCase", TokenKind::Other),
+            text_decoration: field("textDecoration", TokenKind::Other),
+        }
+    }
+
+    /// Resolves each field of the token, returning the CSS properties (and their values) it should be written as.
+    pub fn properties(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> Vec<(&'static str, String)> {
+        let resolve = |value: &Option<TokenValue>, kind: TokenKind| value.as_ref().map(|v| v.with_default_unit(kind).resolve(store, replace_method, theme));
+        let mut properties = vec![];
+
+        if let Some(family) = resolve(&self.font_family, TokenKind::FontFamily) {
+            properties.push(("font-family", family));
+        }
+
+        if let Some(weight) = resolve(&self.font_weight, TokenKind::FontWeights) {
+            let (weight, italic) = font_weight(&weight);
+            properties.push(("font-weight", weight));
+
+            if italic {
+                properties.push(("font-style", String::from("italic")));
+            }
+        }
+
+        if let Some(size) = resolve(&self.font_size, TokenKind::FontSize) {
+            properties.push(("font-size", size));
+        }
+
+        if let Some(line_height) = resolve(&self.line_height, TokenKind::LineHeight) {
+            properties.push(("line-height", self::line_height(&line_height)));
+        }
+
+        if let Some(spacing) = resolve(&self.letter_spacing, TokenKind::LetterSpacing) {
+            properties.push(("letter-spacing", spacing));
+        }
+
+        if let Some(spacing) = resolve(&self.paragraph_spacing, TokenKind::ParagraphSpacing) {
+            properties.push(("margin-block-end", spacing));
+        }
+
+        if let Some(indent) = resolve(&self.paragraph_indent, TokenKind::ParagraphIndent) {
+            properties.push(("text-indent", indent));
+        }
+
+        if let Some(case) = resolve(&self.text_case, TokenKind::TextCase) {
+            // Small caps are a font variant in CSS rather than a text transform.
+            match case.replace('_', "-").to_lowercase().as_str() {
+                "small-caps" => properties.push(("font-variant-caps", String::from("small-caps"))),
+                case => properties.push(("text-transform", case.to_string())),
+            }
+        }
+
+        if let Some(decoration) = resolve(&self.text_decoration, TokenKind::TextDecoration) {
+            properties.push(("text-decoration", decoration));
+        }
+
+        properties
+    }
+
+    /// Builds the value of the `font` shorthand property, i.e. `italic 700 16px/1.5 Inter, sans-serif`. The shorthand
+    /// requires a font size and font family, so `None` is returned if either is missing.
+    pub fn font_shorthand(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> Option<String> {
+        let properties = self.properties(store, replace_method, theme);
+        let property = |name: &str| properties.iter().find(|(n, _)| *n == name).map(|(_, v)| v.as_str());
+
+        let family = property("font-family")?;
+        let size = match property("line-height") {
+            Some(line_height) => format!("{}/{}", property("font-size")?, line_height),
+            None => property("font-size")?.to_string(),
+        };
+
+        let parts = [property("font-style"), property("font-weight"), Some(size.as_str()), Some(family)];
+
+        Some(parts.into_iter().flatten().collect::<Vec<&str>>().join(" "))
+    }
+
+    /// The names of the tokens referenced by any of the fields.
+    pub fn references(&self) -> Vec<String> {
+        [
+            &self.font_family,
+            &self.font_weight,
+            &self.font_size,
+            &self.line_height,
+            &self.letter_spacing,
+            &self.paragraph_spacing,
+            &self.paragraph_indent,
+            &self.text_case,
+            &self.text_decoration,
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(|value| value.references())
+        .collect()
+    }
+
+    /// Renders the value as JSON, keeping the Tokens Studio keys. Static values keep their types, anything that needs
+    /// enriching is written as the resulting string.
+    pub fn to_json(&self, store: &dyn TokenStore, replace_method: ReplaceMethod, theme: &Option<String>) -> serde_json::Value {
+        let fields = [
+            ("fontFamily", &self.font_family),
+            ("fontWeight", &self.font_weight),
+            ("fontSize", &self.font_size),
+            ("lineHeight", &self.line_height),
+            ("letterSpacing", &self.letter_spacing),
+            ("paragraphSpacing", &self.paragraph_spacing),
+            ("paragraphIndent", &self.paragraph_indent),
+            ("textCase", &self.text_case),
+            ("textDecoration", &self.text_decoration),
+        ];
+
+        let mut j = json!({});
+        for (key, value) in fields {
+            if let Some(value) = value {
+                j[key] = if value.has_references() {
+                    json!(value.resolve(store, replace_method, theme))
+                } else {
+                    value.to_json()
+                };
+            }
+        }
+
+        j
+    }
+}
+
+/// Converts a Figma font style (i.e. `Bold` or `Semi Bold Italic`) to a CSS font weight, along with whether the
+/// style is italic. Numeric weights, and anything we don't recognize, are passed through as they are.
+fn font_weight(weight: &str) -> (String, bool) {
+    let name = weight.to_lowercase().replace([' ', '-', '_'], "");
+    let italic = name.ends_with("italic");
+    let name = name.trim_end_matches("italic");
+
+    match FONT_WEIGHTS.iter().find(|(n, _)| *n == name) {
+        Some((_, value)) => (value.to_string(), italic),
+        // "Italic" on its own is a regular weight.
+        None if italic && name.is_empty() => (String::from("400"), true),
+        None => (weight.to_string(), false),
+    }
+}
+
+/// Tokens Studio writes line heights as percentages of the font size, or `AUTO`. A unitless multiplier is preferred
+/// in CSS as it's inherited as a ratio, rather than as a fixed size.
+fn line_height(line_height: &str) -> String {
+    if line_height.eq_ignore_ascii_case("auto") {
+        return String::from("normal");
+    }
+
+    match line_height.strip_suffix('%').and_then(|n| n.parse::<f64>().ok()) {
+        Some(percentage) => css_math::Value::new(percentage / 100.0, None).to_string(),
+        None => line_height.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token_store::test_utils::MockStore;
+    use test_case::test_case;
+
+    fn typography(value: serde_json::Value) -> TypographyValue {
+        TypographyValue::from_json(&value)
+    }
+
+    #[test]
+    fn maps_fields_to_css_properties() {
+        let value = typography(json!({
+            "fontFamily": "Inter",
+            "fontWeight": "Bold",
+            "fontSize": "16px",
+            "lineHeight": "150%",
+            "letterSpacing": "0.5px",
+            "paragraphSpacing": "12px",
+            "paragraphIndent": "2em",
+            "textCase": "uppercase",
+            "textDecoration": "underline"
+        }));
+
+        assert_eq!(
+            value.properties(&MockStore::default(), ReplaceMethod::StaticValues, &None),
+            vec![
+                ("font-family", String::from("Inter")),
+                ("font-weight", String::from("700")),
+                ("font-size", String::from("16px")),
+                ("line-height", String::from("1.5")),
+                ("letter-spacing", String::from("0.5px")),
+                ("margin-block-end", String::from("12px")),
+                ("text-indent", String::from("2em")),
+                ("text-transform", String::from("uppercase")),
+                ("text-decoration", String::from("underline")),
+            ]
+        );
+    }
+
+    #[test_case("150%", "1.5" ; "percentage")]
+    #[test_case("AUTO", "normal" ; "auto")]
+    #[test_case("24px", "24px" ; "dimension")]
+    fn line_heights(input: &str, expected: &str) {
+        assert_eq!(line_height(input), expected);
+    }
+
+    #[test_case("Regular", "400", false ; "regular")]
+    #[test_case("Semi Bold", "600", false ; "semi bold")]
+    #[test_case("Bold Italic", "700", true ; "bold italic")]
+    #[test_case("Italic", "400", true ; "italic")]
+    #[test_case("500", "500", false ; "numeric")]
+    fn font_weights(input: &str, expected: &str, italic: bool) {
+        assert_eq!(font_weight(input), (expected.to_string(), italic));
+    }
+
+    #[test]
+    fn small_caps() {
+        let value = typography(json!({ "textCase": "small_caps" }));
+
+        assert_eq!(
+            value.properties(&MockStore::default(), ReplaceMethod::StaticValues, &None),
+            vec![("font-variant-caps", String::from("small-caps"))]
+        );
+    }
+
+    #[test]
+    fn font_shorthand() {
+        let store = MockStore::default();
+        let value = typography(json!({
+            "fontFamily": "{font.family.body}",
+            "fontWeight": "Bold Italic",
+            "fontSize": "16px",
+            "lineHeight": "AUTO"
+        }));
+
+        assert_eq!(
+            value.font_shorthand(&store, ReplaceMethod::CssVariables, &None),
+            Some(String::from("italic 700 16px/normal var(--font-family-body)"))
+        );
+
+        // The shorthand can't be written without a font size.
+        assert_eq!(typography(json!({ "fontFamily": "Inter" })).font_shorthand(&store, ReplaceMethod::StaticValues, &None), None);
+    }
+
+    #[test]
+    fn to_json() {
+        let value = typography(json!({ "fontFamily": ["Inter", "sans-serif"], "fontWeight": 700, "fontSize": "{font.size.md}" }));
+
+        assert_eq!(
+            value.to_json(&MockStore::default(), ReplaceMethod::CssVariables, &None),
+            json!({ "fontFamily": ["Inter", "sans-serif"], "fontWeight": 700, "fontSize": "var(--font-size-md)" })
+        );
+    }
+}
@@ -1,13 +1,97 @@
 use convert_case::{Boundary, Case, Casing};
+use regex::Captures;
+use serde::{Deserialize, Deserializer};
+
+use crate::regex::{REGEX_HB, REGEX_PX};
+
+/// The boundaries between the words of a token name, when converting it to another case.
+pub(crate) const BOUNDARIES: [Boundary; 5] = [Boundary::LowerUpper, Boundary::Underscore, Boundary::Hyphen, Boundary::Space, Boundary::Acronym];
 
 /// Converts strings of various casings to a valid KebabCase CSS string that can be used for variable names
-/// class-names etc.
-pub fn css_stringify(s: &String) -> String {
-	let boundaries: [Boundary; 5] = [Boundary::LowerUpper, Boundary::Underscore, Boundary::Hyphen, Boundary::Space, Boundary::Acronym];
-	s.replace(".", "-").with_boundaries(&boundaries).to_case(Case::Kebab)
+/// class-names etc. Any characters that aren't valid in a CSS identifier are escaped, see `css_escape`
+pub fn css_stringify(s: &str) -> String {
+	css_escape(&s.replace(".", "-").with_boundaries(&BOUNDARIES).to_case(Case::Kebab))
+}
+
+/// Escapes a string to be used as a CSS identifier (following https://drafts.csswg.org/cssom/#serialize-an-identifier),
+/// i.e. `1/2` becomes `\31 \/2`. Letters, digits, `-`, `_` and any non-ASCII characters are written as they are.
+pub fn css_escape(s: &str) -> String {
+	escape(s, true)
+}
+
+/// Escapes a string to be used as the name of a custom property after its leading `--`, i.e. `1/2` becomes `1\/2`. The
+/// name may start with a digit or a hyphen, so only the characters that aren't valid in it are escaped.
+pub fn css_escape_name(s: &str) -> String {
+	escape(s, false)
+}
+
+fn escape(s: &str, identifier: bool) -> String {
+	let mut escaped = String::with_capacity(s.len());
+	let starts_with_hyphen = s.starts_with('-');
+
+	for (i, c) in s.chars().enumerate() {
+		// An identifier can't start with a digit, or a hyphen followed by a digit, so these are escaped as code points.
+		let leading_digit = identifier && c.is_ascii_digit() && (i == 0 || (i == 1 && starts_with_hyphen));
+
+		match c {
+			'\0' => escaped.push('\u{FFFD}'),
+			'\u{1}'..='\u{1f}' | '\u{7f}' => escaped.push_str(&format!("\\{:x} ", c as u32)),
+			_ if leading_digit => escaped.push_str(&format!("\\{:x} ", c as u32)),
+			// A lone hyphen isn't an identifier.
+			'-' if identifier && s == "-" => escaped.push_str("\\-"),
+			'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => escaped.push(c),
+			_ if !c.is_ascii() => escaped.push(c),
+			_ => {
+				escaped.push('\\');
+				escaped.push(c);
+			}
+		}
+	}
+
+	escaped
+}
+
+/// The names of the tokens referenced in a value, i.e. `["spacing.base"]` for `{spacing.base} * 2`
+pub fn references(value: &str) -> Vec<String> {
+	REGEX_HB.captures_iter(value).map(|caps| caps[1].to_string()).collect()
+}
+
+/// The names of the tokens referenced in the strings of a json value, i.e. `["color.black"]` for
+/// `{ "color": "{color.black}", "width": 1 }`. Only strings are searched, as the braces of objects aren't references.
+pub fn json_references(value: &serde_json::Value) -> Vec<String> {
+	match value {
+		serde_json::Value::String(s) => references(s),
+		serde_json::Value::Array(items) => items.iter().flat_map(json_references).collect(),
+		serde_json::Value::Object(fields) => fields.values().flat_map(json_references).collect(),
+		_ => vec![],
+	}
+}
+
+/// Converts every pixel value in a CSS value to rem, relative to the given base font size (i.e. `calc(100% - 16px)`
+/// becomes `calc(100% - 1rem)` with a base of 16.)
+pub fn px_to_rem(value: &str, base_font_size: f64) -> String {
+	REGEX_PX
+		.replace_all(value, |caps: &Captures| {
+			let px = caps[2].parse::<f64>().unwrap_or_default();
+			format!("{}{}", &caps[1], css_math::Value::new(px / base_font_size, Some(String::from("rem"))))
+		})
+		.to_string()
+}
+
+/// Deserializes a field that Tokens Studio may write as either a string or a number (i.e. `"1px"` or `1`) as a string.
+pub(crate) fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	match serde_json::Value::deserialize(deserializer)? {
+		serde_json::Value::String(s) => Ok(s),
+		serde_json::Value::Number(n) => Ok(n.to_string()),
+		value => Err(serde::de::Error::custom(format!("expected a string or a number, found {}", value))),
+	}
 }
 
 #[cfg(test)]
+#[allow(clippy::unnecessary_to_owned)]
 mod tests {
 	use super::*;
 	use test_case::test_case;
@@ -16,7 +100,55 @@ mod tests {
 	#[test_case("text.headings.h1.fontSize", "text-headings-h1-font-size")]
 	#[test_case("myCompositionToken", "my-composition-token")]
 	#[test_case("ColorPalette.primaryColor.100", "color-palette-primary-color-100")]
+	#[test_case("space.1.5", "space-1-5" ; "decimal")]
+	#[test_case("icon.size.1/2", "icon-size-1\\/2" ; "slash")]
+	#[test_case("color.brand@2x", "color-brand\\@2x" ; "at")]
+	#[test_case("color.🎨 brand", "color-🎨-brand" ; "emoji")]
+	#[test_case("100.gray", "\\31 00-gray" ; "leading digit")]
 	fn to_css_compatible_string(input: &str, expected: &str) {
 		assert_eq!(css_stringify(&input.to_string()), expected.to_string());
 	}
+
+	#[test_case("font-size", "font-size" ; "valid")]
+	#[test_case("2xl", "\\32 xl" ; "leading digit")]
+	#[test_case("-2xl", "-\\32 xl" ; "hyphen and digit")]
+	#[test_case("-", "\\-" ; "hyphen")]
+	#[test_case("a b.c", "a\\ b\\.c" ; "punctuation")]
+	#[test_case("a\u{7f}b\0", "a\\7f b\u{FFFD}" ; "control characters")]
+	#[test_case("café", "café" ; "non ascii")]
+	fn escapes_identifiers(input: &str, expected: &str) {
+		assert_eq!(css_escape(input), expected.to_string());
+	}
+
+	#[test_case("100-gray", "100-gray" ; "leading digit")]
+	#[test_case("-2xl", "-2xl" ; "hyphen and digit")]
+	#[test_case("-", "-" ; "hyphen")]
+	#[test_case("icon-1/2", "icon-1\\/2" ; "punctuation")]
+	fn escapes_names(input: &str, expected: &str) {
+		assert_eq!(css_escape_name(input), expected.to_string());
+	}
+
+	#[test_case("{spacing.base} * 2", vec!["spacing.base"] ; "math")]
+	#[test_case("rgba({color.black}, {opacity.half})", vec!["color.black", "opacity.half"] ; "several")]
+	#[test_case("16px", vec![] ; "none")]
+	fn finds_references(input: &str, expected: Vec<&str>) {
+		assert_eq!(references(input), expected);
+	}
+
+	#[test_case(serde_json::json!({ "color": "{color.black}", "width": 1, "style": "solid" }), vec!["color.black"] ; "object")]
+	#[test_case(serde_json::json!([{ "x": "{space.sm}" }, "{space.md}"]), vec!["space.sm", "space.md"] ; "array")]
+	#[test_case(serde_json::json!({ "color": "#000000", "width": 1 }), vec![] ; "none")]
+	fn finds_json_references(input: serde_json::Value, expected: Vec<&str>) {
+		assert_eq!(json_references(&input), expected);
+	}
+
+	#[test_case("16px", "1rem" ; "dimension")]
+	#[test_case("-8px", "-0.5rem" ; "negative")]
+	#[test_case("calc(100% - 24px)", "calc(100% - 1.5rem)" ; "math")]
+	#[test_case("0 4px 8px rgb(0, 0, 0)", "0 0.25rem 0.5rem rgb(0, 0, 0)" ; "list")]
+	#[test_case("var(--spacing-16px)", "var(--spacing-16px)" ; "variable name")]
+	#[test_case("1.5em", "1.5em" ; "other units")]
+	fn converts_px_to_rem(input: &str, expected: &str) {
+		assert_eq!(px_to_rem(input, 16.0), expected.to_string());
+	}
 }
\ No newline at end of file